@@ -1,6 +1,6 @@
 use abi_stable::std_types::{RString, RVec};
 use rush_plugin::*;
-use std::env;
+use std::{env, os::unix::fs::MetadataExt, path::Path};
 
 #[plugin_name]
 pub fn plugin_name() -> RString {
@@ -14,7 +14,9 @@ pub fn print_desc() {
 
 #[print_help]
 pub fn print_help() {
-    eprintln!("pwd");
+    eprintln!("pwd [-L | -P]");
+    eprintln!("  -L  print the logical directory from $PWD, keeping symbolic links (default)");
+    eprintln!("  -P  print the physical directory, with symbolic links resolved");
 }
 
 #[print_version]
@@ -22,8 +24,40 @@ pub fn print_version() {
     eprintln!("{}", env!("CARGO_PKG_VERSION"));
 }
 
+/// $PWD, if it is an absolute path to the current directory
+fn logical_dir() -> Option<String> {
+    let pwd = env::var("PWD").ok()?;
+    let (logical, current) = (
+        Path::new(&pwd).metadata().ok()?,
+        Path::new(".").metadata().ok()?,
+    );
+
+    let same = logical.dev() == current.dev() && logical.ino() == current.ino();
+    (pwd.starts_with('/') && same).then_some(pwd)
+}
+
+#[complete]
+pub fn complete(_args: RVec<RString>, _cursor: usize) -> RVec<RString> {
+    RVec::from(vec![RString::from("-L"), RString::from("-P")])
+}
+
 #[execute]
-pub fn execute(_args: RVec<RString>) -> ExecResult {
+pub fn execute(args: RVec<RString>) -> ExecResult {
+    let mut physical = false;
+
+    for arg in args.iter() {
+        match arg.as_str() {
+            "-L" => physical = false,
+            "-P" => physical = true,
+            opt => return ExecResult::new(2, &format!("pwd: {opt}: invalid option")),
+        }
+    }
+
+    if !physical && let Some(pwd) = logical_dir() {
+        println!("{pwd}");
+        return ExecResult::default();
+    }
+
     match env::current_dir() {
         Ok(path) => {
             println!("{}", path.to_string_lossy());
@@ -16,7 +16,12 @@ pub struct Command {
     pub print_help: extern "C" fn(),
     pub print_desc: extern "C" fn(),
     pub print_version: extern "C" fn(),
+    #[sabi(last_prefix_field)]
     pub execute: extern "C" fn(RVec<RString>) -> ExecResult,
+    /// Completes the argument at the cursor index among the arguments, absent from plugins
+    /// built before completion was added
+    #[sabi(missing_field(default))]
+    pub complete: Option<extern "C" fn(RVec<RString>, usize) -> RVec<RString>>,
 }
 
 #[repr(C)]
@@ -46,4 +51,3 @@ impl RootModule for CommandRef {
     const NAME: &'static str = "rush_plugin";
     const VERSION_STRINGS: VersionStrings = package_version_strings!();
 }
-
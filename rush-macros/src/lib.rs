@@ -19,10 +19,15 @@ pub fn load(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 print_help: rush_internal_print_help,
                 print_version: rush_internal_print_version,
                 execute: rush_internal_execute,
+                complete: RUSH_INTERNAL_COMPLETE,
             }
             .leak_into_prefix()
         }
 
+        // Shadowed by the definitions of optional entry points
+        #[allow(unused_imports)]
+        use ::rush_plugin::fallback::*;
+
         #[::abi_stable::sabi_extern_fn]
         fn rush_internal_load() {
             #function
@@ -113,3 +118,29 @@ pub fn execute(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+#[proc_macro_attribute]
+pub fn complete(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as syn::ItemFn);
+    let fn_name = &function.sig.ident;
+
+    quote! {
+        #[::abi_stable::sabi_extern_fn]
+        fn rush_internal_complete(
+            args: ::abi_stable::std_types::RVec<::abi_stable::std_types::RString>,
+            cursor: usize,
+        ) -> ::abi_stable::std_types::RVec<::abi_stable::std_types::RString> {
+            #function
+
+            #fn_name(args, cursor)
+        }
+
+        const RUSH_INTERNAL_COMPLETE: ::core::option::Option<
+            extern "C" fn(
+                ::abi_stable::std_types::RVec<::abi_stable::std_types::RString>,
+                usize,
+            ) -> ::abi_stable::std_types::RVec<::abi_stable::std_types::RString>,
+        > = ::core::option::Option::Some(rush_internal_complete);
+    }
+    .into()
+}
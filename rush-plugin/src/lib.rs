@@ -1,2 +1,13 @@
 pub use rush_interface::{self, ExecResult};
-pub use rush_macros::{execute, load, plugin_name, print_desc, print_help, print_version};
+pub use rush_macros::{
+    complete, execute, load, plugin_name, print_desc, print_help, print_version,
+};
+
+/// Entry points used by `#[load]` when a plugin does not define them
+#[doc(hidden)]
+pub mod fallback {
+    use abi_stable::std_types::{RString, RVec};
+
+    pub const RUSH_INTERNAL_COMPLETE: Option<extern "C" fn(RVec<RString>, usize) -> RVec<RString>> =
+        None;
+}
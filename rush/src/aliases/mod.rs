@@ -0,0 +1,64 @@
+use std::{
+    collections::BTreeMap,
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+static ALIASES: OnceLock<RwLock<AliasTable>> = OnceLock::new();
+
+/// Aliases by name, kept sorted for listing and completion
+#[derive(Default)]
+pub struct AliasTable {
+    aliases: BTreeMap<String, String>,
+}
+
+impl AliasTable {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.aliases.insert(name.to_string(), value.to_string());
+    }
+
+    /// Remove an alias, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.aliases.remove(name).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.aliases.clear();
+    }
+
+    /// Aliases and their values, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Whether `name` can be an alias, it must read as a single unquoted word
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace()
+                || matches!(c, '=' | '/' | '$' | '`' | '\\' | '\'' | '"')
+                || matches!(c, '|' | '&' | ';' | '(' | ')' | '<' | '>')
+        })
+}
+
+fn alias_table() -> &'static RwLock<AliasTable> {
+    ALIASES.get_or_init(|| RwLock::new(AliasTable::default()))
+}
+
+pub fn read_aliases() -> anyhow::Result<RwLockReadGuard<'static, AliasTable>> {
+    alias_table()
+        .read()
+        .map_err(|_| anyhow::anyhow!("ALIASES read lock poisoned"))
+}
+
+pub fn write_aliases() -> anyhow::Result<RwLockWriteGuard<'static, AliasTable>> {
+    alias_table()
+        .write()
+        .map_err(|_| anyhow::anyhow!("ALIASES write lock poisoned"))
+}
@@ -0,0 +1,117 @@
+use std::{io::stdin, path::PathBuf};
+
+use anyhow::bail;
+use nix::unistd::isatty;
+
+/// What the shell runs, as chosen by its command line
+pub enum Mode {
+    /// Read commands from the terminal
+    Interactive,
+    /// `rush -c command`
+    Command(String),
+    /// `rush script`
+    Script(PathBuf),
+    /// Read commands from a stdin that is not a terminal
+    Stdin,
+    Help,
+    Version,
+}
+
+pub struct Options {
+    pub mode: Mode,
+    /// Login shells read the profile files before anything else
+    pub login: bool,
+    /// Skip the `rushrc` files of interactive shells
+    pub norc: bool,
+    /// Read this file instead of the `rushrc` files
+    pub rcfile: Option<PathBuf>,
+    /// `$0`
+    pub name: String,
+    /// `$1`, `$2`, ...
+    pub args: Vec<String>,
+}
+
+pub fn print_help() {
+    println!(
+        "Usage: rush [option...] [script [arg...]]\n       rush [option...] -c command [name [arg...]]\n\n\
+         Options:\n  \
+         -c: Runs the commands in the first argument, the next ones set $0, $1, ...\n  \
+         -l, --login: Acts as a login shell and reads the profile files\n  \
+         --norc: Does not read the rushrc files of interactive shells\n  \
+         --rcfile file: Reads file instead of the rushrc files\n  \
+         -h, --help: Prints this help message\n  \
+         -v, --version: Prints the version\n\n\
+         Without a script, commands are read from stdin, interactively when it is a terminal."
+    );
+}
+
+/// Read the shell's own command line, `args` starts with the program name
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Options> {
+    let mut args = args.into_iter();
+    let program = args.next().unwrap_or_else(|| "rush".to_string());
+
+    // `login` starts login shells with a dash before their name
+    let mut login = program.starts_with('-');
+    let mut norc = false;
+    let mut rcfile = None;
+    let mut command = false;
+    let mut operands = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" => command = true,
+            "-l" | "--login" => login = true,
+            "--norc" => norc = true,
+            "--rcfile" => match args.next() {
+                Some(path) => rcfile = Some(PathBuf::from(path)),
+                None => bail!("--rcfile: option requires an argument"),
+            },
+            "-h" | "--help" => return Ok(options(Mode::Help)),
+            "-v" | "--version" => return Ok(options(Mode::Version)),
+            "--" => {
+                operands.extend(args.by_ref());
+            }
+            opt if opt.starts_with('-') && opt.len() > 1 => bail!("{opt}: invalid option"),
+            _ => {
+                // Options end at the first operand, the rest belongs to the script
+                operands.push(arg);
+                operands.extend(args.by_ref());
+            }
+        }
+    }
+
+    let mut operands = operands.into_iter();
+
+    let (mode, name) = if command {
+        let Some(command) = operands.next() else {
+            bail!("-c: option requires an argument");
+        };
+        (Mode::Command(command), operands.next().unwrap_or(program))
+    } else if let Some(script) = operands.next() {
+        (Mode::Script(PathBuf::from(&script)), script)
+    } else if isatty(stdin()).unwrap_or(false) {
+        (Mode::Interactive, program)
+    } else {
+        (Mode::Stdin, program)
+    };
+
+    Ok(Options {
+        mode,
+        login,
+        norc,
+        rcfile,
+        name,
+        args: operands.collect(),
+    })
+}
+
+fn options(mode: Mode) -> Options {
+    Options {
+        mode,
+        login: false,
+        norc: false,
+        rcfile: None,
+        name: String::new(),
+        args: Vec::new(),
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::bail;
+use rush_interface::ExecResult;
+
+use super::{execute_list, report_failure, run_command};
+use crate::{
+    expand, jobs,
+    parser::ast::{CaseArm, Command, List, Word},
+    vars::{self, read_vars, write_vars},
+};
+
+/// A `break`, `continue` or `return` on its way out of the commands it leaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Break(usize),
+    Continue(usize),
+    /// Leave the running function with a status
+    Return(u8),
+}
+
+/// Number of loops being run, which bounds the count given to `break` and `continue`
+static LOOP_DEPTH: AtomicUsize = AtomicUsize::new(0);
+/// Number of functions being run, `return` is only allowed inside one
+static FUNCTION_DEPTH: AtomicUsize = AtomicUsize::new(0);
+static PENDING: Mutex<Option<Flow>> = Mutex::new(None);
+
+/// Leave loops or the running function, the rest of their bodies is skipped
+pub fn request_flow(flow: Flow) -> anyhow::Result<()> {
+    let depth = LOOP_DEPTH.load(Ordering::Relaxed);
+
+    let flow = match flow {
+        Flow::Return(_) if FUNCTION_DEPTH.load(Ordering::Relaxed) == 0 => {
+            bail!("can only `return' from a function")
+        }
+        Flow::Return(_) => flow,
+        Flow::Break(0) | Flow::Continue(0) => bail!("loop count out of range"),
+        _ if depth == 0 => bail!("only meaningful in a `for', `while', or `until' loop"),
+        Flow::Break(count) => Flow::Break(count.min(depth)),
+        Flow::Continue(count) => Flow::Continue(count.min(depth)),
+    };
+
+    set_pending(Some(flow));
+    Ok(())
+}
+
+/// Whether commands are being skipped until a loop or function takes the pending flow
+pub(super) fn is_leaving() -> bool {
+    pending().is_some()
+}
+
+fn pending() -> Option<Flow> {
+    PENDING.lock().map(|pending| *pending).unwrap_or_default()
+}
+
+fn set_pending(control: Option<Flow>) {
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = control;
+    }
+}
+
+/// Keeps a depth counter counting a loop or function for as long as it runs
+struct DepthGuard(&'static AtomicUsize);
+
+impl DepthGuard {
+    fn enter(depth: &'static AtomicUsize) -> Self {
+        depth.fetch_add(1, Ordering::Relaxed);
+        DepthGuard(depth)
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Whether the loop must stop after an iteration, consuming the control meant for it
+fn should_stop() -> bool {
+    // A foreground job killed by Ctrl-C stops every loop, as it would stop a script
+    if jobs::take_interrupted() {
+        set_pending(Some(Flow::Break(LOOP_DEPTH.load(Ordering::Relaxed))));
+    }
+
+    match pending() {
+        None => false,
+        // Left for the function to take
+        Some(Flow::Return(_)) => true,
+        Some(Flow::Break(1)) => {
+            set_pending(None);
+            true
+        }
+        Some(Flow::Continue(1)) => {
+            set_pending(None);
+            false
+        }
+        // Outer loops take the rest
+        Some(Flow::Break(count)) => {
+            set_pending(Some(Flow::Break(count - 1)));
+            true
+        }
+        Some(Flow::Continue(count)) => {
+            set_pending(Some(Flow::Continue(count - 1)));
+            true
+        }
+    }
+}
+
+/// Run a function with its arguments as positional parameters, in a scope for its locals
+pub(super) fn execute_function(body: &Command, args: Vec<String>) -> ExecResult {
+    let saved_args = match write_vars() {
+        Ok(mut vars) => {
+            vars.push_scope();
+            vars.set_positional(args)
+        }
+        Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+    };
+
+    let mut status = {
+        let _guard = DepthGuard::enter(&FUNCTION_DEPTH);
+        run_command(body, false)
+    };
+
+    if let Some(Flow::Return(code)) = pending() {
+        set_pending(None);
+        status = ExecResult::new(code, "");
+    }
+
+    if let Ok(mut vars) = write_vars() {
+        vars.pop_scope();
+        vars.set_positional(saved_args);
+    }
+
+    status
+}
+
+pub(super) fn execute_if(branches: &[(List, List)], else_body: Option<&List>) -> ExecResult {
+    for (condition, body) in branches {
+        let status = execute_list(condition);
+
+        if is_leaving() {
+            return status;
+        }
+        if status.code == 0 {
+            return execute_list(body);
+        }
+    }
+
+    else_body.map_or_else(ExecResult::ok, execute_list)
+}
+
+/// Run a `while` loop, or an `until` loop which runs while the condition fails
+pub(super) fn execute_while(condition: &List, body: &List, until: bool) -> ExecResult {
+    let _guard = DepthGuard::enter(&LOOP_DEPTH);
+    let mut status = ExecResult::ok();
+
+    loop {
+        let tested = execute_list(condition);
+
+        if should_stop() || (tested.code == 0) == until {
+            break;
+        }
+
+        status = execute_list(body);
+
+        if should_stop() {
+            break;
+        }
+    }
+
+    status
+}
+
+pub(super) fn execute_for(name: &str, words: Option<&[Word]>, body: &List) -> ExecResult {
+    let values = match words {
+        Some(words) => match expand::expand_words(words) {
+            Ok(values) => values,
+            Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+        },
+        None => match read_vars() {
+            Ok(vars) => vars.positional().to_vec(),
+            Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+        },
+    };
+
+    let _guard = DepthGuard::enter(&LOOP_DEPTH);
+    let mut status = ExecResult::ok();
+
+    for value in values {
+        if let Err(e) = vars::assign(name, &value) {
+            return report_failure(ExecResult::new(1, &format!("rush: {e}")));
+        }
+
+        status = execute_list(body);
+
+        if should_stop() {
+            break;
+        }
+    }
+
+    status
+}
+
+/// Run the body of the first arm with a pattern matching the word
+pub(super) fn execute_case(word: &Word, arms: &[CaseArm]) -> ExecResult {
+    let text = match expand::expand_value(word) {
+        Ok(text) => text,
+        Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+    };
+
+    for arm in arms {
+        for pattern in &arm.patterns {
+            match expand::matches_pattern(pattern, &text) {
+                Ok(true) => return execute_list(&arm.body),
+                Ok(false) => {}
+                Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+            }
+        }
+    }
+
+    ExecResult::ok()
+}
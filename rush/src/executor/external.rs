@@ -0,0 +1,190 @@
+use std::{
+    env, fs, io,
+    os::unix::{fs::PermissionsExt, process::CommandExt},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use abi_stable::std_types::{RString, RVec};
+use log::debug;
+use nix::{
+    sys::signal::{SigHandler, Signal, signal},
+    unistd::Pid,
+};
+use rush_interface::ExecResult;
+
+use crate::jobs;
+
+pub const COMMAND_NOT_EXECUTABLE: u8 = 126;
+pub const COMMAND_NOT_FOUND: u8 = 127;
+
+/// Signals the shell handles itself, restored to default in every child
+const SHELL_SIGNALS: [Signal; 2] = [Signal::SIGINT, Signal::SIGQUIT];
+
+pub(super) fn set_shell_signals() -> anyhow::Result<()> {
+    // SAFETY: SigIgn installs no handler code
+    unsafe { signal(Signal::SIGQUIT, SigHandler::SigIgn) }?;
+
+    // Ctrl-C only marks the shell as interrupted, which stops its loops
+    jobs::catch_interrupts()?;
+    Ok(())
+}
+
+/// Give a child process the default signal dispositions the shell overrides
+pub(super) fn restore_default_signals() -> io::Result<()> {
+    let signals = SHELL_SIGNALS
+        .into_iter()
+        .chain(jobs::JOB_CONTROL_SIGNALS)
+        .chain([Signal::SIGPIPE]);
+
+    for sig in signals {
+        // SAFETY: signal(2) is async-signal-safe, so it may run between fork and exec
+        unsafe { signal(sig, SigHandler::SigDfl) }?;
+    }
+
+    Ok(())
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Names of the executables found in the `$PATH` directories
+pub fn path_executables() -> Vec<String> {
+    let Some(paths) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+
+    env::split_paths(&paths)
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
+/// Resolve a command name to an executable, searching `$PATH` when the name has no slash
+pub fn find_executable(cmd: &str) -> Option<PathBuf> {
+    if cmd.contains('/') {
+        let path = PathBuf::from(cmd);
+        return is_executable(&path).then_some(path);
+    }
+
+    env::var_os("PATH").and_then(|paths| {
+        env::split_paths(&paths)
+            .map(|dir| dir.join(cmd))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+fn build_command(cmd: &str, path: &Path, args: RVec<RString>) -> Command {
+    let mut command = Command::new(path);
+    command.arg0(cmd).args(args.iter().map(RString::as_str));
+    command
+}
+
+pub fn execute_external(cmd: &str, path: &Path, args: RVec<RString>) -> ExecResult {
+    debug!("Spawn external command: {}", path.display());
+
+    let description = std::iter::once(cmd)
+        .chain(args.iter().map(RString::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut command = build_command(cmd, path, args);
+
+    // SAFETY: the hook only calls async-signal-safe functions
+    unsafe {
+        command.pre_exec(|| {
+            jobs::join_job(None, true);
+            restore_default_signals()
+        });
+    }
+
+    // The job module reaps the child, so it can also notice it being stopped
+    let pid = match command.spawn() {
+        Ok(child) => Pid::from_raw(child.id() as i32),
+        Err(e) => return spawn_error(cmd, e),
+    };
+
+    jobs::assign_job(pid, pid);
+
+    ExecResult::new(jobs::run_foreground(pid, description, &[pid]), "")
+}
+
+/// Replace the current process with an external command, only returns on failure
+pub fn exec_external(cmd: &str, path: &Path, args: RVec<RString>) -> ExecResult {
+    debug!("Exec external command: {}", path.display());
+
+    spawn_error(cmd, build_command(cmd, path, args).exec())
+}
+
+/// Why a command name found nothing to run, a path naming a directory or a file that cannot
+/// be run is not missing
+pub(super) fn not_found(cmd: &str) -> ExecResult {
+    if !cmd.contains('/') {
+        return ExecResult::new(COMMAND_NOT_FOUND, &format!("{cmd}: command not found"));
+    }
+
+    match Path::new(cmd).metadata() {
+        Ok(meta) if meta.is_dir() => {
+            ExecResult::new(COMMAND_NOT_EXECUTABLE, &format!("{cmd}: is a directory"))
+        }
+        Ok(_) => ExecResult::new(COMMAND_NOT_EXECUTABLE, &format!("{cmd}: permission denied")),
+        Err(_) => ExecResult::new(
+            COMMAND_NOT_FOUND,
+            &format!("{cmd}: no such file or directory"),
+        ),
+    }
+}
+
+fn spawn_error(cmd: &str, e: io::Error) -> ExecResult {
+    match e.kind() {
+        io::ErrorKind::NotFound => {
+            ExecResult::new(COMMAND_NOT_FOUND, &format!("{cmd}: command not found"))
+        }
+        io::ErrorKind::PermissionDenied => {
+            ExecResult::new(COMMAND_NOT_EXECUTABLE, &format!("{cmd}: permission denied"))
+        }
+        _ => ExecResult::new(COMMAND_NOT_EXECUTABLE, &format!("{cmd}: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::Permissions;
+
+    use super::*;
+
+    #[test]
+    fn paths_must_name_executable_files() {
+        let dir = env::temp_dir().join(format!("rush-external-{}", std::process::id()));
+        fs::create_dir_all(dir.join("subdir")).unwrap();
+        let script = dir.join("script");
+        let plain = dir.join("plain");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, Permissions::from_mode(0o755)).unwrap();
+        fs::write(&plain, "").unwrap();
+
+        let name = |path: &Path| path.to_string_lossy().into_owned();
+
+        assert_eq!(find_executable(&name(&script)), Some(script.clone()));
+        assert_eq!(find_executable(&name(&plain)), None);
+        assert_eq!(find_executable(&name(&dir.join("subdir"))), None);
+
+        assert_eq!(
+            not_found(&name(&dir.join("subdir"))).code,
+            COMMAND_NOT_EXECUTABLE
+        );
+        assert_eq!(not_found(&name(&plain)).code, COMMAND_NOT_EXECUTABLE);
+        assert_eq!(
+            not_found(&name(&dir.join("missing"))).code,
+            COMMAND_NOT_FOUND
+        );
+        assert_eq!(not_found("missing").code, COMMAND_NOT_FOUND);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+mod control;
+mod external;
+mod pipeline;
+mod redirect;
+mod substitution;
+
+use std::{
+    io::{Write, stderr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use abi_stable::std_types::{RString, RVec};
+use log::debug;
+use rush_interface::ExecResult;
+
+use crate::{
+    expand,
+    functions::{read_functions, write_functions},
+    jobs,
+    parser::{
+        self,
+        ast::{AndOr, AndOrOp, Command, CompoundCommand, List, Pipeline, Separator, SimpleCommand},
+    },
+    plugin::{get_plugin, is_plugin},
+    shell_builtins, vars,
+};
+
+pub use control::{Flow, request_flow};
+pub use external::{COMMAND_NOT_FOUND, find_executable, is_executable, path_executables};
+pub use substitution::capture_output;
+
+pub const SYNTAX_ERROR: u8 = 2;
+
+/// What a command name refers to, in lookup order
+enum CommandKind {
+    Builtin,
+    Function(Arc<Command>),
+    Plugin,
+    External(PathBuf),
+    NotFound,
+}
+
+pub fn execute_user_input(input: &str) -> ExecResult {
+    // An interrupt before this input must not stop its loops
+    jobs::take_interrupted();
+
+    let status = match parser::parse(input) {
+        Ok(list) => execute_list(&list),
+        Err(e) => {
+            vars::set_last_status(SYNTAX_ERROR);
+            ExecResult::new(SYNTAX_ERROR, &format!("rush: {e}"))
+        }
+    };
+
+    debug!("{:?}", status);
+
+    report_status(&status);
+    status
+}
+
+fn report_status(status: &ExecResult) {
+    if status.code.ne(&0) && !status.message.is_empty() {
+        let _ = stderr().write_all(format!("{}\n", status.message).as_bytes());
+    }
+}
+
+/// Report an error right away, so it is not lost when more commands follow
+fn report_failure(status: ExecResult) -> ExecResult {
+    report_status(&status);
+    ExecResult::new(status.code, "")
+}
+
+fn execute_list(list: &List) -> ExecResult {
+    let mut status = ExecResult::ok();
+
+    for item in &list.items {
+        // The rest of a loop body is skipped after `break` or `continue`
+        if control::is_leaving() {
+            break;
+        }
+
+        status = match item.separator {
+            Separator::Sequential => execute_and_or(&item.and_or),
+            Separator::Background => {
+                let status = pipeline::execute_background(&item.and_or);
+                vars::set_last_status(status.code);
+                status
+            }
+        };
+    }
+
+    status
+}
+
+/// Run pipelines joined by `&&` and `||`, skipping those the previous status rules out
+fn execute_and_or(and_or: &AndOr) -> ExecResult {
+    let mut status = execute_pipeline(&and_or.first);
+
+    for (op, pipeline) in &and_or.rest {
+        let should_run = match op {
+            AndOrOp::And => status.code == 0,
+            AndOrOp::Or => status.code != 0,
+        };
+
+        if should_run && !control::is_leaving() {
+            status = execute_pipeline(pipeline);
+        }
+    }
+
+    status
+}
+
+fn execute_pipeline(pipeline: &Pipeline) -> ExecResult {
+    let status = match pipeline.commands.as_slice() {
+        [command] => run_command(command, false),
+        _ => pipeline::execute_pipeline(pipeline),
+    };
+
+    vars::set_last_status(status.code);
+
+    status
+}
+
+/// Run a single command, `replace` lets an external command take over the current process
+fn run_command(command: &Command, replace: bool) -> ExecResult {
+    match command {
+        Command::Simple(simple) => execute_simple_command(simple, replace),
+        Command::Compound(compound, redirects) => {
+            let _redirects = match redirect::apply_redirects(redirects) {
+                Ok(guard) => guard,
+                Err(status) => return report_failure(status),
+            };
+
+            match compound {
+                CompoundCommand::Group(body) => execute_list(body),
+                CompoundCommand::If {
+                    branches,
+                    else_body,
+                } => control::execute_if(branches, else_body.as_ref()),
+                CompoundCommand::While { condition, body } => {
+                    control::execute_while(condition, body, false)
+                }
+                CompoundCommand::Until { condition, body } => {
+                    control::execute_while(condition, body, true)
+                }
+                CompoundCommand::For { name, words, body } => {
+                    control::execute_for(name, words.as_deref(), body)
+                }
+                CompoundCommand::Case { word, arms } => control::execute_case(word, arms),
+            }
+        }
+        Command::FunctionDef(name, body) => match write_functions() {
+            Ok(mut functions) => {
+                functions.define(name, (**body).clone());
+                ExecResult::ok()
+            }
+            Err(e) => report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+        },
+    }
+}
+
+fn execute_simple_command(command: &SimpleCommand, replace: bool) -> ExecResult {
+    // Forget substitutions made by earlier commands
+    vars::take_substitution_status();
+
+    // Restores the shell's descriptors when the command is done
+    let _redirects = match redirect::apply_redirects(&command.redirects) {
+        Ok(guard) => guard,
+        Err(status) => return report_failure(status),
+    };
+
+    let mut args: RVec<RString> = match expand::expand_words(&command.words) {
+        Ok(fields) => fields.into_iter().map(RString::from).collect(),
+        Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+    };
+
+    // Without a command, assignments change the shell's own variables, one after the other
+    if args.is_empty() {
+        for assignment in &command.assignments {
+            let assigned = expand::expand_value(&assignment.value)
+                .and_then(|value| vars::assign(&assignment.name, &value));
+
+            if let Err(e) = assigned {
+                return report_failure(ExecResult::new(1, &format!("rush: {e}")));
+            }
+        }
+
+        return ExecResult::new(vars::take_substitution_status().unwrap_or(0), "");
+    }
+
+    let mut assignments = Vec::with_capacity(command.assignments.len());
+    for assignment in &command.assignments {
+        match expand::expand_value(&assignment.value) {
+            Ok(value) => assignments.push((assignment.name.clone(), value)),
+            Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+        }
+    }
+
+    // Undone when the command is done
+    let _temporary = match vars::set_temporary(&assignments) {
+        Ok(temporary) => temporary,
+        Err(e) => return report_failure(ExecResult::new(1, &format!("rush: {e}"))),
+    };
+
+    let cmd = args.remove(0);
+    let status = dispatch(&cmd, args, replace);
+
+    // Report while the redirections are active, so `2>` captures shell messages too
+    report_status(&status);
+
+    ExecResult::new(status.code, "")
+}
+
+fn resolve_command(cmd: &str) -> CommandKind {
+    if shell_builtins::builtins_registry().is_ok_and(|reg| reg.contains(cmd)) {
+        CommandKind::Builtin
+    } else if let Some(body) = read_functions().ok().and_then(|table| table.get(cmd)) {
+        CommandKind::Function(body)
+    } else if is_plugin(cmd) {
+        CommandKind::Plugin
+    } else if let Some(path) = find_executable(cmd) {
+        CommandKind::External(path)
+    } else {
+        CommandKind::NotFound
+    }
+}
+
+/// Run a command by name, trying builtins, then functions, then plugins, then `$PATH`
+fn dispatch(cmd: &str, args: RVec<RString>, replace: bool) -> ExecResult {
+    match resolve_command(cmd) {
+        CommandKind::Builtin => match shell_builtins::builtins_registry() {
+            Ok(builtins_reg) => builtins_reg.execute(cmd, args),
+            Err(e) => ExecResult::new(1, &format!("{e}")),
+        },
+        CommandKind::Function(body) => {
+            control::execute_function(&body, args.into_iter().map(String::from).collect())
+        }
+        CommandKind::Plugin => execute_command(cmd, args),
+        CommandKind::External(path) if replace => external::exec_external(cmd, &path, args),
+        CommandKind::External(path) => external::execute_external(cmd, &path, args),
+        CommandKind::NotFound => external::not_found(cmd),
+    }
+}
+
+pub fn execute_command(cmd: &str, argv: RVec<RString>) -> ExecResult {
+    match get_plugin(cmd) {
+        Ok(plugin) => plugin.execute()(argv),
+        Err(e) => ExecResult::new(101, &format!("{e}")),
+    }
+}
+
+pub fn init_module() -> anyhow::Result<()> {
+    // The shell must outlive Ctrl-C and Ctrl-\ aimed at its foreground command
+    external::set_shell_signals()?;
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::{
+    fs::File,
+    io::{Write, stderr, stdout},
+    os::fd::{AsRawFd, OwnedFd},
+    process,
+};
+
+use log::debug;
+use nix::{
+    fcntl::OFlag,
+    unistd::{ForkResult, Pid, dup2_stdin, dup2_stdout, fork, pipe2},
+};
+use rush_interface::ExecResult;
+
+use super::external;
+use crate::{
+    jobs,
+    parser::ast::{AndOr, Command, Pipeline},
+    plugin,
+};
+
+/// Fork a child that joins the job `pgid` (a new one if `None`) and exits with the status of `body`
+fn fork_child(pgid: Option<Pid>, foreground: bool, body: impl FnOnce() -> u8) -> nix::Result<Pid> {
+    let _ = stdout().flush();
+
+    // SAFETY: the shell is single threaded, so the child starts from a consistent state
+    match unsafe { fork() }? {
+        ForkResult::Parent { child } => {
+            jobs::assign_job(child, pgid.unwrap_or(child));
+            Ok(child)
+        }
+        ForkResult::Child => {
+            jobs::join_job(pgid, foreground);
+            let _ = external::restore_default_signals();
+            jobs::disable_job_control();
+
+            exit_child(body())
+        }
+    }
+}
+
+/// Run every command of a pipeline in its own process, connected by pipes
+pub(super) fn execute_pipeline(pipeline: &Pipeline) -> ExecResult {
+    let commands = &pipeline.commands;
+    let mut pids = Vec::with_capacity(commands.len());
+    let mut pgid = None;
+    let mut stdin: Option<OwnedFd> = None;
+
+    for (index, command) in commands.iter().enumerate() {
+        let (next_stdin, stdout) = if index + 1 < commands.len() {
+            match pipe2(OFlag::O_CLOEXEC) {
+                Ok((read, write)) => (Some(read), Some(write)),
+                Err(e) => {
+                    let _ = writeln!(stderr(), "rush: pipe: {e}");
+                    break;
+                }
+            }
+        } else {
+            (None, None)
+        };
+
+        preload_plugin(command);
+
+        let stage_stdin = stdin.take();
+        let unused = next_stdin.as_ref().map(AsRawFd::as_raw_fd);
+        let spawned = fork_child(pgid, true, move || {
+            if let Some(fd) = unused {
+                // SAFETY: the child never returns to the owner of this descriptor
+                unsafe { nix::libc::close(fd) };
+            }
+            if let Some(fd) = stage_stdin {
+                let _ = dup2_stdin(fd);
+            }
+            if let Some(fd) = stdout {
+                let _ = dup2_stdout(fd);
+            }
+
+            let status = super::run_command(command, true);
+            super::report_status(&status);
+            status.code
+        });
+
+        match spawned {
+            Ok(pid) => {
+                debug!("Spawned pipeline stage: {}", pid);
+                pgid.get_or_insert(pid);
+                pids.push(pid);
+            }
+            Err(e) => {
+                let _ = writeln!(stderr(), "rush: fork: {e}");
+                break;
+            }
+        }
+
+        // Parent copies of the pipe ends are closed here, so readers see EOF
+        stdin = next_stdin;
+    }
+
+    let Some(pgid) = pgid else {
+        return ExecResult::new(1, "");
+    };
+
+    let code = jobs::run_foreground(pgid, pipeline.to_string(), &pids);
+
+    // A stage that failed to start leaves the pipeline without its last command
+    if pids.len() < commands.len() {
+        return ExecResult::new(1, "");
+    }
+
+    ExecResult::new(code, "")
+}
+
+/// Run commands in a forked subshell that becomes a background job
+pub(super) fn execute_background(and_or: &AndOr) -> ExecResult {
+    // Without job control, background jobs must not compete for the terminal
+    let detach_stdin = !jobs::is_job_control_enabled();
+
+    let spawned = fork_child(None, false, || {
+        if detach_stdin && let Ok(null) = File::open("/dev/null") {
+            let _ = dup2_stdin(null);
+        }
+
+        let status = match (and_or.rest.as_slice(), and_or.first.commands.as_slice()) {
+            ([], [command]) => super::run_command(command, true),
+            _ => super::execute_and_or(and_or),
+        };
+        super::report_status(&status);
+        status.code
+    });
+
+    match spawned {
+        Ok(pid) => match jobs::add_background(pid, and_or.to_string(), &[pid]) {
+            Ok(_) => ExecResult::ok(),
+            Err(e) => ExecResult::new(1, &format!("rush: {e}")),
+        },
+        Err(e) => ExecResult::new(1, &format!("rush: fork: {e}")),
+    }
+}
+
+/// Load plugins in the shell, so later pipelines don't load them again in every child
+fn preload_plugin(command: &Command) {
+    if let Command::Simple(simple) = command
+        && let Some(word) = simple.words.first()
+    {
+        let _ = plugin::get_plugin(&word.to_unquoted());
+    }
+}
+
+/// Flush buffered output and leave a forked child
+pub(super) fn exit_child(code: u8) -> ! {
+    let _ = stdout().flush();
+    let _ = stderr().flush();
+
+    process::exit(code as i32)
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Write, stdout},
+    os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use log::debug;
+use nix::{
+    errno::Errno,
+    fcntl::{FcntlArg, fcntl},
+    libc,
+};
+use rush_interface::ExecResult;
+
+use crate::{
+    expand,
+    parser::ast::{Redirect, RedirectKind},
+};
+
+/// Saved descriptors are moved above this number, out of the way of user redirections
+const SAVED_FD_BASE: RawFd = 64;
+
+/// Descriptors replaced by redirections, put back when the guard is dropped
+#[derive(Default)]
+pub(super) struct RedirectGuard {
+    saved: Vec<(RawFd, Option<OwnedFd>)>,
+}
+
+impl RedirectGuard {
+    /// Remember the current state of `fd` before it is first replaced
+    fn save(&mut self, fd: RawFd) {
+        if self.saved.iter().any(|(saved_fd, _)| *saved_fd == fd) {
+            return;
+        }
+
+        // SAFETY: the descriptor is only borrowed for the duration of the call
+        let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+        let copy = fcntl(borrowed, FcntlArg::F_DUPFD_CLOEXEC(SAVED_FD_BASE))
+            .ok()
+            // SAFETY: F_DUPFD_CLOEXEC returned a new descriptor that nothing else owns
+            .map(|raw| unsafe { OwnedFd::from_raw_fd(raw) });
+
+        self.saved.push((fd, copy));
+    }
+}
+
+impl Drop for RedirectGuard {
+    fn drop(&mut self) {
+        let _ = stdout().flush();
+
+        for (fd, copy) in self.saved.drain(..).rev() {
+            match copy {
+                Some(copy) => {
+                    let _ = dup_onto(copy.as_raw_fd(), fd);
+                }
+                None => close_fd(fd),
+            }
+        }
+    }
+}
+
+fn dup_onto(src: RawFd, dst: RawFd) -> nix::Result<()> {
+    // SAFETY: dup2 only manipulates the descriptor table
+    Errno::result(unsafe { libc::dup2(src, dst) }).map(drop)
+}
+
+fn close_fd(fd: RawFd) {
+    // SAFETY: the redirection owns `fd` until it is restored
+    unsafe { libc::close(fd) };
+}
+
+fn is_open(fd: RawFd) -> bool {
+    // SAFETY: F_GETFD only queries the descriptor
+    unsafe { libc::fcntl(fd, libc::F_GETFD) != -1 }
+}
+
+fn open_target(kind: RedirectKind, path: &str) -> anyhow::Result<File> {
+    let mut options = OpenOptions::new();
+
+    match kind {
+        RedirectKind::Input | RedirectKind::DupInput => options.read(true),
+        RedirectKind::Append | RedirectKind::AppendAll => options.append(true).create(true),
+        _ => options.write(true).create(true).truncate(true),
+    };
+
+    options.open(path).map_err(|e| match e.raw_os_error() {
+        Some(code) => anyhow::anyhow!(Errno::from_raw(code).desc()),
+        None => e.into(),
+    })
+}
+
+/// Apply redirections to the shell's own descriptors until the guard is dropped
+pub(super) fn apply_redirects(redirects: &[Redirect]) -> Result<RedirectGuard, ExecResult> {
+    let mut guard = RedirectGuard::default();
+
+    if redirects.is_empty() {
+        return Ok(guard);
+    }
+
+    // Anything already buffered belongs to the old stdout
+    let _ = stdout().flush();
+
+    for redirect in redirects {
+        let target = match expand::expand_word(&redirect.target).as_deref() {
+            Ok([target]) => target.to_string(),
+            Ok(_) => {
+                let message = format!("rush: {}: ambiguous redirect", redirect.target);
+                return Err(ExecResult::new(1, &message));
+            }
+            Err(e) => return Err(ExecResult::new(1, &format!("rush: {e}"))),
+        };
+        apply_redirect(&mut guard, redirect, &target)
+            .map_err(|e| ExecResult::new(1, &format!("rush: {target}: {e}")))?;
+    }
+
+    Ok(guard)
+}
+
+fn apply_redirect(
+    guard: &mut RedirectGuard,
+    redirect: &Redirect,
+    target: &str,
+) -> anyhow::Result<()> {
+    let default_fd = match redirect.kind {
+        RedirectKind::Input | RedirectKind::DupInput => libc::STDIN_FILENO,
+        _ => libc::STDOUT_FILENO,
+    };
+    let fd = redirect.fd.map_or(default_fd, |fd| fd as RawFd);
+
+    debug!("Redirect fd {} {:?} {}", fd, redirect.kind, target);
+
+    match redirect.kind {
+        RedirectKind::DupInput | RedirectKind::DupOutput if target == "-" => {
+            guard.save(fd);
+            close_fd(fd);
+        }
+        RedirectKind::DupInput | RedirectKind::DupOutput if target.parse::<RawFd>().is_ok() => {
+            let src = target.parse::<RawFd>()?;
+            anyhow::ensure!(is_open(src), "bad file descriptor");
+            guard.save(fd);
+            dup_onto(src, fd)?;
+        }
+        RedirectKind::DupInput => anyhow::bail!("ambiguous redirect"),
+        // `>&file` without a descriptor number is the same as `&>file`
+        RedirectKind::DupOutput if redirect.fd.is_none() => {
+            redirect_both(guard, RedirectKind::OutputAll, target)?
+        }
+        RedirectKind::DupOutput => anyhow::bail!("ambiguous redirect"),
+        RedirectKind::OutputAll | RedirectKind::AppendAll => {
+            redirect_both(guard, redirect.kind, target)?
+        }
+        RedirectKind::Input | RedirectKind::Output | RedirectKind::Append => {
+            let file = open_target(redirect.kind, target)?;
+            guard.save(fd);
+            dup_onto(file.as_raw_fd(), fd)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Send both stdout and stderr to a file
+fn redirect_both(
+    guard: &mut RedirectGuard,
+    kind: RedirectKind,
+    target: &str,
+) -> anyhow::Result<()> {
+    let file = open_target(kind, target)?;
+
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        guard.save(fd);
+        dup_onto(file.as_raw_fd(), fd)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::{
+    fs::File,
+    io::{Read, Write, stdout},
+};
+
+use nix::{
+    errno::Errno,
+    fcntl::OFlag,
+    sys::wait::{WaitStatus, waitpid},
+    unistd::{ForkResult, Pid, dup2_stdout, fork, pipe2},
+};
+
+use super::{external, pipeline};
+use crate::{jobs, parser::ast::List};
+
+/// Run commands in a subshell and collect what they write to stdout, with their status
+pub fn capture_output(list: &List) -> anyhow::Result<(String, u8)> {
+    let (read, write) = pipe2(OFlag::O_CLOEXEC)?;
+    let _ = stdout().flush();
+
+    // SAFETY: the shell is single threaded, so the child starts from a consistent state
+    match unsafe { fork() }? {
+        ForkResult::Child => {
+            drop(read);
+            let _ = external::restore_default_signals();
+            jobs::disable_job_control();
+
+            // Builtins and plugins print through the same descriptor as external commands
+            let _ = dup2_stdout(&write);
+            drop(write);
+
+            let status = super::execute_list(list);
+            super::report_status(&status);
+
+            pipeline::exit_child(status.code)
+        }
+        ForkResult::Parent { child } => {
+            drop(write);
+
+            let mut output = Vec::new();
+            let read_result = File::from(read).read_to_end(&mut output);
+            let code = wait_child(child);
+            read_result?;
+
+            Ok((String::from_utf8_lossy(&output).into_owned(), code))
+        }
+    }
+}
+
+fn wait_child(pid: Pid) -> u8 {
+    loop {
+        match waitpid(pid, None) {
+            Ok(WaitStatus::Exited(_, code)) => return code as u8,
+            Ok(WaitStatus::Signaled(_, sig, _)) => return 128 + sig as u8,
+            Ok(_) | Err(Errno::EINTR) => {}
+            Err(_) => return 1,
+        }
+    }
+}
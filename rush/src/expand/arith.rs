@@ -0,0 +1,441 @@
+use std::fmt;
+
+use anyhow::{bail, ensure};
+
+use crate::vars::{self, read_vars};
+
+/// Variables may hold expressions themselves, evaluated up to this depth
+const MAX_RECURSION: usize = 32;
+
+/// Operators sorted so that longer ones are tried first
+const OPERATORS: [&str; 39] = [
+    "<<=", ">>=", "**", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "&=", "^=", "|=", "+", "-", "*", "/", "%", "<", ">", "=", "!", "~", "&", "^",
+    "|", "?", ":", "(", ")", ",",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Name(name) => write!(f, "{name}"),
+            Token::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+/// Evaluate an arithmetic expression, assignments change shell variables
+pub fn evaluate(expression: &str) -> anyhow::Result<i64> {
+    evaluate_at(expression, 0).map_err(|e| anyhow::anyhow!("{}: {e}", expression.trim()))
+}
+
+fn evaluate_at(expression: &str, depth: usize) -> anyhow::Result<i64> {
+    ensure!(depth < MAX_RECURSION, "expression recursion level exceeded");
+
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        pos: 0,
+        depth,
+    };
+
+    if parser.tokens.is_empty() {
+        return Ok(0);
+    }
+
+    let value = parser.comma(true)?;
+
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        bail!("syntax error in expression (error token is \"{token}\")");
+    }
+
+    Ok(value)
+}
+
+fn tokenize(expression: &str) -> anyhow::Result<Vec<Token>> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c.is_ascii_digit() {
+            let start = pos;
+            while pos < chars.len() && chars[pos].is_ascii_alphanumeric() {
+                pos += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            tokens.push(Token::Number(parse_number(&text)?));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            tokens.push(Token::Name(chars[start..pos].iter().collect()));
+        } else {
+            let rest: String = chars[pos..chars.len().min(pos + 3)].iter().collect();
+            let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) else {
+                bail!("syntax error: invalid arithmetic operator (error token is \"{c}\")");
+            };
+            pos += op.chars().count();
+            tokens.push(Token::Op(op));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a decimal, `0x` hexadecimal or `0` octal constant
+fn parse_number(text: &str) -> anyhow::Result<i64> {
+    let parsed = if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16)
+    } else if text.len() > 1 && text.starts_with('0') {
+        i64::from_str_radix(&text[1..], 8)
+    } else {
+        text.parse()
+    };
+
+    parsed.map_err(|_| anyhow::anyhow!("value too great for base (error token is \"{text}\")"))
+}
+
+/// Binding power of binary operators, higher binds tighter
+fn precedence(op: &str) -> Option<u8> {
+    let prec = match op {
+        "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" => 6,
+        "<" | "<=" | ">" | ">=" => 7,
+        "<<" | ">>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        "**" => 11,
+        _ => return None,
+    };
+
+    Some(prec)
+}
+
+fn apply(op: &str, left: i64, right: i64) -> anyhow::Result<i64> {
+    let value = match op {
+        "||" => (left != 0 || right != 0) as i64,
+        "&&" => (left != 0 && right != 0) as i64,
+        "|" => left | right,
+        "^" => left ^ right,
+        "&" => left & right,
+        "==" => (left == right) as i64,
+        "!=" => (left != right) as i64,
+        "<" => (left < right) as i64,
+        "<=" => (left <= right) as i64,
+        ">" => (left > right) as i64,
+        ">=" => (left >= right) as i64,
+        "<<" => left.wrapping_shl(right as u32),
+        ">>" => left.wrapping_shr(right as u32),
+        "+" => checked(left.checked_add(right))?,
+        "-" => checked(left.checked_sub(right))?,
+        "*" => checked(left.checked_mul(right))?,
+        "/" | "%" if right == 0 => bail!("division by 0"),
+        "/" => checked(left.checked_div(right))?,
+        "%" => checked(left.checked_rem(right))?,
+        "**" => {
+            ensure!(right >= 0, "exponent less than 0");
+            checked(left.checked_pow(u32::try_from(right).unwrap_or(u32::MAX)))?
+        }
+        _ => bail!("syntax error: invalid arithmetic operator (error token is \"{op}\")"),
+    };
+
+    Ok(value)
+}
+
+/// The result of a checked operation, which is `None` when it does not fit in 64 bits
+fn checked(value: Option<i64>) -> anyhow::Result<i64> {
+    value.ok_or_else(|| anyhow::anyhow!("arithmetic overflow"))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> anyhow::Result<()> {
+        ensure!(self.peek_op() == Some(op), "syntax error: `{op}' expected");
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Value of a variable, itself evaluated as an expression; `eval` is false in skipped branches
+    fn variable(&self, name: &str, eval: bool) -> anyhow::Result<i64> {
+        if !eval {
+            return Ok(0);
+        }
+
+        let value = read_vars()?.get(name).unwrap_or_default().to_string();
+
+        match value.trim() {
+            "" => Ok(0),
+            value => evaluate_at(value, self.depth + 1),
+        }
+    }
+
+    fn assign(&self, name: &str, value: i64, eval: bool) -> anyhow::Result<i64> {
+        if eval {
+            vars::assign(name, &value.to_string())?;
+        }
+
+        Ok(value)
+    }
+
+    /// `a, b` evaluates both and keeps the last
+    fn comma(&mut self, eval: bool) -> anyhow::Result<i64> {
+        let mut value = self.assignment(eval)?;
+
+        while self.peek_op() == Some(",") {
+            self.pos += 1;
+            value = self.assignment(eval)?;
+        }
+
+        Ok(value)
+    }
+
+    fn assignment(&mut self, eval: bool) -> anyhow::Result<i64> {
+        if let (Some(Token::Name(name)), Some(Token::Op(op))) =
+            (self.tokens.get(self.pos), self.tokens.get(self.pos + 1))
+            && op.ends_with('=')
+            && !matches!(*op, "==" | "!=" | "<=" | ">=")
+        {
+            let (name, op) = (name.clone(), *op);
+            self.pos += 2;
+
+            let right = self.assignment(eval)?;
+            let value = match op.strip_suffix('=') {
+                Some("") => right,
+                Some(binary) if eval => apply(binary, self.variable(&name, eval)?, right)?,
+                _ => 0,
+            };
+
+            return self.assign(&name, value, eval);
+        }
+
+        self.ternary(eval)
+    }
+
+    fn ternary(&mut self, eval: bool) -> anyhow::Result<i64> {
+        let condition = self.binary(1, eval)?;
+
+        if self.peek_op() != Some("?") {
+            return Ok(condition);
+        }
+
+        self.pos += 1;
+        let then = self.assignment(eval && condition != 0)?;
+        self.expect(":")?;
+        let otherwise = self.assignment(eval && condition == 0)?;
+
+        Ok(if condition != 0 { then } else { otherwise })
+    }
+
+    /// Binary operators by precedence climbing, `&&` and `||` skip their right side when decided
+    fn binary(&mut self, min_prec: u8, eval: bool) -> anyhow::Result<i64> {
+        let mut left = self.unary(eval)?;
+
+        while let Some(op) = self.peek_op()
+            && let Some(prec) = precedence(op)
+            && prec >= min_prec
+        {
+            self.pos += 1;
+
+            let eval_right = match op {
+                "&&" => eval && left != 0,
+                "||" => eval && left == 0,
+                _ => eval,
+            };
+            // `**` is right associative
+            let next_prec = if op == "**" { prec } else { prec + 1 };
+            let right = self.binary(next_prec, eval_right)?;
+
+            left = if eval { apply(op, left, right)? } else { 0 };
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self, eval: bool) -> anyhow::Result<i64> {
+        let Some(op) = self.peek_op() else {
+            return self.postfix(eval);
+        };
+
+        match op {
+            "++" | "--" => {
+                self.pos += 1;
+                let Some(Token::Name(name)) = self.peek().cloned() else {
+                    bail!("syntax error: operand expected");
+                };
+                self.pos += 1;
+
+                let step = if op == "++" { 1 } else { -1 };
+                let value = checked(self.variable(&name, eval)?.checked_add(step))?;
+                self.assign(&name, value, eval)
+            }
+            "!" | "~" | "-" | "+" => {
+                self.pos += 1;
+                let value = self.unary(eval)?;
+
+                Ok(match op {
+                    "!" => (value == 0) as i64,
+                    "~" => !value,
+                    "-" => checked(value.checked_neg())?,
+                    _ => value,
+                })
+            }
+            _ => self.postfix(eval),
+        }
+    }
+
+    fn postfix(&mut self, eval: bool) -> anyhow::Result<i64> {
+        match self.peek().cloned() {
+            Some(Token::Number(value)) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(Token::Name(name)) => {
+                self.pos += 1;
+                let value = self.variable(&name, eval)?;
+
+                match self.peek_op() {
+                    Some(op @ ("++" | "--")) => {
+                        self.pos += 1;
+                        let step = if op == "++" { 1 } else { -1 };
+                        self.assign(&name, checked(value.checked_add(step))?, eval)?;
+                        Ok(value)
+                    }
+                    _ => Ok(value),
+                }
+            }
+            Some(Token::Op("(")) => {
+                self.pos += 1;
+                let value = self.comma(eval)?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            _ => bail!("syntax error: operand expected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(expression: &str) -> String {
+        evaluate(expression).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn follows_operator_precedence() {
+        let cases = [
+            ("1 + 2 * 3", 7),
+            ("(1 + 2) * 3", 9),
+            ("10 - 4 - 3", 3),
+            ("2 ** 3 ** 2", 512),
+            ("-2 ** 2", 4),
+            ("1 + 2 << 1", 6),
+            ("1 < 2 == 1", 1),
+            ("6 & 3 | 8", 10),
+            ("1 | 2 ^ 3", 1),
+            ("0 || 1 && 0", 0),
+            ("!0 + ~0", 0),
+            ("7 % 4 * 2", 6),
+            ("1 ? 2 : 3 ? 4 : 5", 2),
+            ("0 ? 2 : 0 ? 4 : 5", 5),
+            ("1, 2, 3", 3),
+            ("", 0),
+        ];
+
+        for (expression, value) in cases {
+            assert_eq!(evaluate(expression).unwrap(), value, "{expression}");
+        }
+    }
+
+    #[test]
+    fn reads_number_bases() {
+        assert_eq!(evaluate("0x1f").unwrap(), 31);
+        assert_eq!(evaluate("010").unwrap(), 8);
+        assert_eq!(evaluate("0").unwrap(), 0);
+        assert!(error("09").contains("value too great for base"));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert_eq!(error("1 / 0"), "1 / 0: division by 0");
+        assert_eq!(error("1 % 0"), "1 % 0: division by 0");
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        for expression in [
+            "9223372036854775807 + 1",
+            "-9223372036854775807 - 2",
+            "4611686018427387904 * 2",
+            "2 ** 63",
+            "2 ** 4294967296",
+            "(-9223372036854775807 - 1) / -1",
+            "(-9223372036854775807 - 1) % -1",
+            "-(-9223372036854775807 - 1)",
+        ] {
+            assert!(
+                error(expression).ends_with("arithmetic overflow"),
+                "{expression}"
+            );
+        }
+
+        assert!(error("9223372036854775808").contains("value too great for base"));
+    }
+
+    #[test]
+    fn short_circuits_skipped_operands() {
+        assert_eq!(evaluate("0 && 1 / 0").unwrap(), 0);
+        assert_eq!(evaluate("1 || 1 / 0").unwrap(), 1);
+        assert_eq!(evaluate("1 ? 2 : 1 / 0").unwrap(), 2);
+    }
+
+    #[test]
+    fn assigns_variables() {
+        assert_eq!(evaluate("ARITH_TEST = 5, ARITH_TEST *= 2").unwrap(), 10);
+        assert_eq!(evaluate("ARITH_TEST++").unwrap(), 10);
+        assert_eq!(evaluate("--ARITH_TEST + 0").unwrap(), 10);
+        assert_eq!(evaluate("ARITH_TEST").unwrap(), 10);
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert!(error("1 +").contains("operand expected"));
+        assert!(error("(1").contains("`)' expected"));
+        assert!(error("1 2").contains("error token is \"2\""));
+        assert!(error("1 @ 2").contains("invalid arithmetic operator"));
+        assert!(error("2 ** -1").contains("exponent less than 0"));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::parser::ast::{Word, WordPart};
+
+/// Unquoted characters stay visible to brace expansion, everything else is kept whole
+#[derive(Debug, Clone)]
+enum Piece {
+    Char(char),
+    Part(WordPart),
+}
+
+/// Expand `{a,b}` and `{1..10}` in the unquoted text of a word
+pub fn expand_braces(word: &Word) -> Vec<Word> {
+    let mut pieces = Vec::new();
+
+    for part in &word.parts {
+        match part {
+            WordPart::Literal(text) => pieces.extend(text.chars().map(Piece::Char)),
+            _ => pieces.push(Piece::Part(part.clone())),
+        }
+    }
+
+    expand_pieces(pieces).into_iter().map(to_word).collect()
+}
+
+fn expand_pieces(pieces: Vec<Piece>) -> Vec<Vec<Piece>> {
+    for (start, piece) in pieces.iter().enumerate() {
+        if !matches!(piece, Piece::Char('{')) {
+            continue;
+        }
+
+        let Some((end, commas)) = find_close(&pieces, start) else {
+            continue;
+        };
+
+        let alternatives: Vec<Vec<Piece>> = if commas.is_empty() {
+            match sequence(&pieces[start + 1..end]) {
+                Some(items) => items
+                    .into_iter()
+                    .map(|item| item.chars().map(Piece::Char).collect())
+                    .collect(),
+                None => continue,
+            }
+        } else {
+            let mut bounds = vec![start];
+            bounds.extend(&commas);
+            bounds.push(end);
+
+            bounds
+                .windows(2)
+                .map(|pair| pieces[pair[0] + 1..pair[1]].to_vec())
+                .collect()
+        };
+
+        let (prefix, suffix) = (&pieces[..start], &pieces[end + 1..]);
+
+        // The suffix may hold more braces, and alternatives nested ones
+        return alternatives
+            .into_iter()
+            .flat_map(|alternative| {
+                let mut combined = prefix.to_vec();
+                combined.extend(alternative);
+                combined.extend_from_slice(suffix);
+                expand_pieces(combined)
+            })
+            .collect();
+    }
+
+    vec![pieces]
+}
+
+/// Find the brace closing the one at `start`, with the positions of its top level commas
+fn find_close(pieces: &[Piece], start: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0;
+    let mut commas = Vec::new();
+
+    for (index, piece) in pieces.iter().enumerate().skip(start) {
+        match piece {
+            Piece::Char('{') => depth += 1,
+            Piece::Char('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((index, commas));
+                }
+            }
+            Piece::Char(',') if depth == 1 => commas.push(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Items of `{first..last}` or `{first..last..step}`, over integers or single characters
+fn sequence(pieces: &[Piece]) -> Option<Vec<String>> {
+    let text = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Char(c) => Some(*c),
+            Piece::Part(_) => None,
+        })
+        .collect::<Option<String>>()?;
+
+    let bounds: Vec<&str> = text.split("..").collect();
+    let (first, last, step) = match bounds.as_slice() {
+        [first, last] => (*first, *last, None),
+        [first, last, step] => (*first, *last, Some(step.parse::<i64>().ok()?)),
+        _ => return None,
+    };
+    let step = step.map_or(1, i64::unsigned_abs).max(1) as usize;
+
+    if let (Ok(start), Ok(end)) = (first.parse::<i64>(), last.parse::<i64>()) {
+        // `{01..10}` pads every number to the widest bound
+        let padded = [first, last].iter().any(|bound| {
+            bound.trim_start_matches('-').len() > 1
+                && bound.trim_start_matches('-').starts_with('0')
+        });
+        let width = if padded {
+            first.len().max(last.len())
+        } else {
+            0
+        };
+
+        let items: Vec<i64> = if start <= end {
+            (start..=end).step_by(step).collect()
+        } else {
+            (end..=start).rev().step_by(step).collect()
+        };
+
+        return Some(items.iter().map(|n| format!("{n:0width$}")).collect());
+    }
+
+    let mut first_chars = first.chars();
+    let mut last_chars = last.chars();
+
+    match (
+        first_chars.next(),
+        first_chars.next(),
+        last_chars.next(),
+        last_chars.next(),
+    ) {
+        (Some(start), None, Some(end), None)
+            if start.is_ascii_alphabetic() && end.is_ascii_alphabetic() =>
+        {
+            let items: Vec<char> = if start <= end {
+                (start..=end).step_by(step).collect()
+            } else {
+                (end..=start).rev().step_by(step).collect()
+            };
+
+            Some(items.iter().map(char::to_string).collect())
+        }
+        _ => None,
+    }
+}
+
+fn to_word(pieces: Vec<Piece>) -> Word {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+
+    for piece in pieces {
+        match piece {
+            Piece::Char(c) => literal.push(c),
+            Piece::Part(part) => {
+                if !literal.is_empty() {
+                    parts.push(WordPart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(part);
+            }
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(WordPart::Literal(literal));
+    }
+
+    Word { parts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(parts: Vec<WordPart>) -> Vec<String> {
+        expand_braces(&Word { parts })
+            .iter()
+            .map(Word::to_unquoted)
+            .collect()
+    }
+
+    fn expand_literal(text: &str) -> Vec<String> {
+        expand(vec![WordPart::Literal(text.to_string())])
+    }
+
+    #[test]
+    fn expands_alternatives() {
+        assert_eq!(expand_literal("a{b,c}d"), ["abd", "acd"]);
+        assert_eq!(expand_literal("{a,b}{1,2}"), ["a1", "a2", "b1", "b2"]);
+        assert_eq!(
+            expand_literal("file.{rs,,toml}"),
+            ["file.rs", "file.", "file.toml"]
+        );
+    }
+
+    #[test]
+    fn expands_nested_braces() {
+        assert_eq!(expand_literal("{a,b{1,2}}c"), ["ac", "b1c", "b2c"]);
+        assert_eq!(expand_literal("{{a,b},c}"), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn leaves_single_items_and_unclosed_braces_literal() {
+        assert_eq!(expand_literal("{a}"), ["{a}"]);
+        assert_eq!(expand_literal("{}"), ["{}"]);
+        assert_eq!(expand_literal("{a,b"), ["{a,b"]);
+        assert_eq!(expand_literal("a}b,c{"), ["a}b,c{"]);
+    }
+
+    #[test]
+    fn quoted_braces_and_commas_are_literal() {
+        let quoted = |text: &str| WordPart::Quoted(text.to_string());
+        let literal = |text: &str| WordPart::Literal(text.to_string());
+
+        assert_eq!(expand(vec![quoted("{"), literal("a,b}")]), ["{a,b}"]);
+        assert_eq!(
+            expand(vec![literal("{a"), quoted(","), literal("b}")]),
+            ["{a,b}"]
+        );
+        assert_eq!(
+            expand(vec![literal("{a,"), quoted("}"), literal(",b}")]),
+            ["a", "}", "b"]
+        );
+    }
+
+    #[test]
+    fn expands_numeric_ranges() {
+        assert_eq!(expand_literal("{1..4}"), ["1", "2", "3", "4"]);
+        assert_eq!(expand_literal("{3..1}"), ["3", "2", "1"]);
+        assert_eq!(expand_literal("{-1..1}"), ["-1", "0", "1"]);
+        assert_eq!(expand_literal("{1..10..3}"), ["1", "4", "7", "10"]);
+        assert_eq!(expand_literal("{10..1..-4}"), ["10", "6", "2"]);
+        assert_eq!(expand_literal("{1..2..0}"), ["1", "2"]);
+    }
+
+    #[test]
+    fn pads_ranges_with_leading_zeros() {
+        assert_eq!(expand_literal("{08..11}"), ["08", "09", "10", "11"]);
+        assert_eq!(expand_literal("{1..010..4}"), ["001", "005", "009"]);
+        assert_eq!(expand_literal("{-05..5..5}"), ["-05", "000", "005"]);
+    }
+
+    #[test]
+    fn expands_character_ranges() {
+        assert_eq!(expand_literal("{a..e..2}"), ["a", "c", "e"]);
+        assert_eq!(expand_literal("{c..a}"), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn leaves_invalid_ranges_literal() {
+        assert_eq!(expand_literal("{1..b}"), ["{1..b}"]);
+        assert_eq!(expand_literal("{1..2..x}"), ["{1..2..x}"]);
+        assert_eq!(expand_literal("{ab..c}"), ["{ab..c}"]);
+        assert_eq!(expand_literal("{1...3}"), ["{1...3}"]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::{fs, path::Path};
+
+use super::pattern;
+
+/// Paths matching a filename pattern, sorted; `**` also matches any number of directories
+pub fn glob(pattern: &str) -> Vec<String> {
+    let (mut paths, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (vec!["/".to_string()], rest),
+        None => (vec![String::new()], pattern),
+    };
+
+    let components: Vec<&str> = rest.split('/').collect();
+
+    for (index, component) in components.iter().enumerate() {
+        let is_last = index + 1 == components.len();
+        let mut next = Vec::new();
+
+        for base in &paths {
+            match *component {
+                // Doubled or trailing slashes only require a directory
+                "" => {
+                    if dir_of(base).is_dir() {
+                        next.push(base.clone());
+                    }
+                }
+                "**" => {
+                    if !base.is_empty() || !is_last {
+                        next.push(base.clone());
+                    }
+                    walk(base, is_last, &mut next);
+                }
+                _ if !pattern::has_wildcards(component) => {
+                    next.push(format!("{base}{}", pattern::unescape(component)));
+                }
+                _ => {
+                    for name in read_names(base, component) {
+                        next.push(format!("{base}{name}"));
+                    }
+                }
+            }
+        }
+
+        // Separate the matches from the next component
+        paths = next
+            .into_iter()
+            .map(
+                |path| match is_last || path.is_empty() || path.ends_with('/') {
+                    true => path,
+                    false => format!("{path}/"),
+                },
+            )
+            .collect();
+    }
+
+    // Literal components were not checked while walking
+    paths.retain(|path| !path.is_empty() && fs::symlink_metadata(path).is_ok());
+    paths.sort();
+    paths.dedup();
+
+    paths
+}
+
+/// The directory a path prefix refers to, the current one when it is empty
+fn dir_of(base: &str) -> &Path {
+    Path::new(if base.is_empty() { "." } else { base })
+}
+
+/// Names in a directory matching one component, hidden ones only when the pattern starts with a dot
+fn read_names(base: &str, component: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir_of(base)) else {
+        return Vec::new();
+    };
+
+    let show_hidden = component.starts_with('.');
+
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| show_hidden || !name.starts_with('.'))
+        .filter(|name| pattern::matches(component, name))
+        .collect()
+}
+
+/// Add everything below `base` for `**`, only directories unless it ends the pattern
+fn walk(base: &str, include_files: bool, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir_of(base)) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = format!("{base}{name}");
+        // Symlinked directories are not followed, so cycles cannot occur
+        let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+
+        if is_dir || include_files {
+            out.push(path.clone());
+        }
+        if is_dir {
+            walk(&format!("{path}/"), include_files, out);
+        }
+    }
+}
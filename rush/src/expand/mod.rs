@@ -0,0 +1,374 @@
+mod arith;
+mod brace;
+mod glob;
+mod pattern;
+mod tilde;
+
+use anyhow::bail;
+
+use crate::{
+    executor,
+    parser::ast::{List, Parameter, ParameterOp, Word, WordPart},
+    vars::{self, read_vars, write_vars},
+};
+
+/// Field separators used when `$IFS` is unset
+const DEFAULT_IFS: &str = " \t\n";
+
+/// Expand parameters in every word, split the results into fields and match filename patterns
+pub fn expand_words(words: &[Word]) -> anyhow::Result<Vec<String>> {
+    let mut fields = Vec::new();
+
+    for word in words.iter().flat_map(brace::expand_braces) {
+        for field in expand_fields(&word)? {
+            if !pattern::has_wildcards(&field.pattern) {
+                fields.push(field.text);
+                continue;
+            }
+
+            let matches = glob::glob(&field.pattern);
+
+            if matches.is_empty() {
+                let (nullglob, failglob) = read_vars()
+                    .map(|vars| (vars.option("nullglob"), vars.option("failglob")))
+                    .unwrap_or_default();
+
+                if failglob {
+                    bail!("no match: {}", field.text);
+                } else if !nullglob {
+                    fields.push(field.text);
+                }
+            } else {
+                fields.extend(matches);
+            }
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Expand a single word without filename matching, which may produce no field at all or several
+pub fn expand_word(word: &Word) -> anyhow::Result<Vec<String>> {
+    Ok(expand_fields(word)?
+        .into_iter()
+        .map(|field| field.text)
+        .collect())
+}
+
+/// Whether the text matches a word used as a pattern, as in a `case` arm
+pub fn matches_pattern(word: &Word, text: &str) -> anyhow::Result<bool> {
+    Ok(pattern::matches(
+        &expand_pattern(&tilde::expand_tilde(word))?,
+        text,
+    ))
+}
+
+/// Expand a word without splitting it, as for the value of `NAME=value`
+pub fn expand_value(word: &Word) -> anyhow::Result<String> {
+    expand_unsplit(&tilde::expand_tilde(word))
+}
+
+/// Expand a word as if it was between double quotes
+fn expand_unsplit(word: &Word) -> anyhow::Result<String> {
+    let mut fields = Fields::default();
+    expand_parts(&word.parts, true, &mut fields)?;
+
+    let texts: Vec<String> = fields
+        .finish()
+        .into_iter()
+        .map(|field| field.text)
+        .collect();
+    Ok(texts.join(" "))
+}
+
+fn expand_fields(word: &Word) -> anyhow::Result<Vec<Field>> {
+    let word = tilde::expand_tilde(word);
+    let mut fields = Fields::default();
+    expand_parts(&word.parts, false, &mut fields)?;
+
+    Ok(fields.finish())
+}
+
+#[derive(Default)]
+struct Field {
+    text: String,
+    /// The same text as a filename pattern, with quoted characters escaped
+    pattern: String,
+}
+
+/// Fields produced by a word, growing as its parts are expanded
+#[derive(Default)]
+struct Fields {
+    fields: Vec<Field>,
+    current: Field,
+    /// Whether `current` counts as a field even when empty, as after `""`
+    active: bool,
+}
+
+impl Fields {
+    /// Append quoted text, which is never split nor used as a pattern
+    fn push_str(&mut self, text: &str) {
+        self.current.text.push_str(text);
+        self.current.pattern.push_str(&pattern::escape(text));
+        self.active = true;
+    }
+
+    /// Append unquoted text, which keeps its meaning as a pattern
+    fn push_unquoted(&mut self, text: &str) {
+        self.current.text.push_str(text);
+        self.current.pattern.push_str(text);
+        self.active = true;
+    }
+
+    /// Append the result of an unquoted expansion, starting a new field at every separator
+    fn push_split(&mut self, text: &str, ifs: &str) {
+        for c in text.chars() {
+            if ifs.contains(c) {
+                self.end_field();
+            } else {
+                self.current.text.push(c);
+                self.current.pattern.push(c);
+                self.active = true;
+            }
+        }
+    }
+
+    /// Append the value of an expansion, which is only split when it was not quoted
+    fn push_expansion(&mut self, value: &str, quoted: bool) {
+        if quoted {
+            self.push_str(value);
+        } else {
+            self.push_split(value, &ifs());
+        }
+    }
+
+    fn end_field(&mut self) {
+        if self.active {
+            self.fields.push(std::mem::take(&mut self.current));
+            self.active = false;
+        }
+    }
+
+    fn finish(mut self) -> Vec<Field> {
+        self.end_field();
+        self.fields
+    }
+}
+
+fn expand_parts(parts: &[WordPart], quoted: bool, fields: &mut Fields) -> anyhow::Result<()> {
+    for part in parts {
+        match part {
+            WordPart::Literal(text) if !quoted => fields.push_unquoted(text),
+            WordPart::Literal(text) | WordPart::Quoted(text) => fields.push_str(text),
+            WordPart::DoubleQuoted(inner) => {
+                // `"$@"` without arguments expands to nothing rather than to an empty field
+                if !matches!(inner.as_slice(), [WordPart::Parameter(param)] if is_all_arguments(param))
+                {
+                    fields.active = true;
+                }
+                expand_parts(inner, true, fields)?;
+            }
+            WordPart::Parameter(param) if quoted && is_all_arguments(param) => {
+                let positional = read_vars()?.positional().to_vec();
+
+                for (index, arg) in positional.iter().enumerate() {
+                    if index > 0 {
+                        fields.end_field();
+                    }
+                    fields.push_str(arg);
+                }
+            }
+            WordPart::Parameter(param) => fields.push_expansion(&expand_parameter(param)?, quoted),
+            WordPart::CommandSubst(list) => fields.push_expansion(&command_output(list)?, quoted),
+            WordPart::Arithmetic(expr) => fields.push_expansion(&arithmetic(expr)?, quoted),
+        }
+    }
+
+    Ok(())
+}
+
+/// Output of a command substitution without its trailing newlines, its status becomes `$?`
+fn command_output(list: &List) -> anyhow::Result<String> {
+    let (output, code) = executor::capture_output(list)?;
+    vars::set_substitution_status(code);
+
+    Ok(output.trim_end_matches('\n').to_string())
+}
+
+/// Result of `$((expression))`, after expanding the expression's parameters
+fn arithmetic(expr: &Word) -> anyhow::Result<String> {
+    Ok(arith::evaluate(&expand_unsplit(expr)?)?.to_string())
+}
+
+/// Whether the parameter is a plain `$@`, which keeps every argument a separate field
+fn is_all_arguments(param: &Parameter) -> bool {
+    param.name == "@" && param.op == ParameterOp::Value
+}
+
+fn ifs() -> String {
+    read_vars()
+        .ok()
+        .and_then(|vars| vars.get("IFS").map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_IFS.to_string())
+}
+
+/// Value of a parameter by name, `None` when it is unset
+fn lookup(name: &str) -> anyhow::Result<Option<String>> {
+    let vars = read_vars()?;
+
+    let value = match name {
+        "?" => Some(vars.last_status().to_string()),
+        "$" => Some(vars.shell_pid().to_string()),
+        "#" => Some(vars.positional().len().to_string()),
+        "@" | "*" => Some(vars.positional().join(" ")),
+        "0" => Some(vars.shell_name().to_string()),
+        _ => match name.parse::<usize>() {
+            Ok(index) => index
+                .checked_sub(1)
+                .and_then(|index| vars.positional().get(index))
+                .cloned(),
+            Err(_) => vars.get(name).map(str::to_string),
+        },
+    };
+
+    Ok(value)
+}
+
+fn expand_parameter(param: &Parameter) -> anyhow::Result<String> {
+    let value = lookup(&param.name)?;
+
+    let expanded = match &param.op {
+        ParameterOp::Value => value.unwrap_or_default(),
+        ParameterOp::Length if matches!(param.name.as_str(), "@" | "*") => {
+            read_vars()?.positional().len().to_string()
+        }
+        ParameterOp::Length => value.unwrap_or_default().chars().count().to_string(),
+        ParameterOp::Default(word) => match value {
+            Some(value) if !value.is_empty() => value,
+            _ => expand_to_string(word)?,
+        },
+        ParameterOp::Assign(word) => match value {
+            Some(value) if !value.is_empty() => value,
+            _ => {
+                if !vars::is_valid_name(&param.name) {
+                    bail!("${}: cannot assign in this way", param.name);
+                }
+
+                let value = expand_to_string(word)?;
+                write_vars()?.set(&param.name, &value);
+                value
+            }
+        },
+        ParameterOp::RemovePrefix(word) | ParameterOp::RemoveLongestPrefix(word) => {
+            let value = value.unwrap_or_default();
+            let pattern = expand_pattern(word)?;
+
+            // Shortest ends first, longest first for `##`
+            let mut ends: Vec<usize> = value
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([value.len()])
+                .collect();
+            if matches!(param.op, ParameterOp::RemoveLongestPrefix(_)) {
+                ends.reverse();
+            }
+
+            ends.into_iter()
+                .find(|&end| pattern::matches(&pattern, &value[..end]))
+                .map_or(value.clone(), |end| value[end..].to_string())
+        }
+        ParameterOp::RemoveSuffix(word) | ParameterOp::RemoveLongestSuffix(word) => {
+            let value = value.unwrap_or_default();
+            let pattern = expand_pattern(word)?;
+
+            // Shortest suffixes first, longest first for `%%`
+            let mut starts: Vec<usize> = value
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([value.len()])
+                .rev()
+                .collect();
+            if matches!(param.op, ParameterOp::RemoveLongestSuffix(_)) {
+                starts.reverse();
+            }
+
+            starts
+                .into_iter()
+                .find(|&start| pattern::matches(&pattern, &value[start..]))
+                .map_or(value.clone(), |start| value[..start].to_string())
+        }
+    };
+
+    Ok(expanded)
+}
+
+/// Expand a word to a single string, as for the operand of `${VAR:-word}`
+fn expand_to_string(word: &Word) -> anyhow::Result<String> {
+    Ok(expand_word(word)?.join(" "))
+}
+
+/// Expand a word into a pattern where only unquoted characters keep their special meaning
+fn expand_pattern(word: &Word) -> anyhow::Result<String> {
+    fn push_parts(parts: &[WordPart], quoted: bool, out: &mut String) -> anyhow::Result<()> {
+        for part in parts {
+            match part {
+                WordPart::Literal(text) if !quoted => out.push_str(text),
+                WordPart::Literal(text) | WordPart::Quoted(text) => {
+                    out.push_str(&pattern::escape(text))
+                }
+                WordPart::DoubleQuoted(inner) => push_parts(inner, true, out)?,
+                WordPart::Parameter(param) => {
+                    let value = expand_parameter(param)?;
+                    out.push_str(&if quoted {
+                        pattern::escape(&value)
+                    } else {
+                        value
+                    });
+                }
+                WordPart::CommandSubst(list) => {
+                    let value = command_output(list)?;
+                    out.push_str(&if quoted {
+                        pattern::escape(&value)
+                    } else {
+                        value
+                    });
+                }
+                WordPart::Arithmetic(expr) => out.push_str(&arithmetic(expr)?),
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut pattern = String::new();
+    push_parts(&word.parts, false, &mut pattern)?;
+
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_shortest_and_longest_matches() {
+        write_vars().unwrap().set("REMOVE_TEST", "a.b.c");
+
+        let remove = |op: fn(Word) -> ParameterOp, pattern: &str| {
+            expand_parameter(&Parameter {
+                name: "REMOVE_TEST".to_string(),
+                op: op(Word {
+                    parts: vec![WordPart::Literal(pattern.to_string())],
+                }),
+            })
+            .unwrap()
+        };
+
+        assert_eq!(remove(ParameterOp::RemovePrefix, "*."), "b.c");
+        assert_eq!(remove(ParameterOp::RemoveLongestPrefix, "*."), "c");
+        assert_eq!(remove(ParameterOp::RemoveSuffix, ".*"), "a.b");
+        assert_eq!(remove(ParameterOp::RemoveLongestSuffix, ".*"), "a");
+        assert_eq!(remove(ParameterOp::RemoveLongestPrefix, "*"), "");
+        assert_eq!(remove(ParameterOp::RemoveLongestSuffix, "x*"), "a.b.c");
+    }
+}
@@ -0,0 +1,225 @@
+/// Whether `text` matches a shell pattern with `*`, `?` and `[...]`, a backslash quotes the next character
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    match_from(&pattern, &text)
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    let mut p = 0;
+    let mut t = 0;
+    // Where to retry after the last `*`: its end in the pattern and the text it stops at
+    let mut star = None;
+
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, t));
+        } else if let Some(next) = match_one(pattern, p, text[t]) {
+            p = next;
+            t += 1;
+        } else if let Some((after_star, skipped)) = star {
+            // Let the last `*` take one more character, earlier ones never need to
+            p = after_star;
+            t = skipped + 1;
+            star = Some((after_star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match one character against the pattern element at `p`, returning where the next one starts
+fn match_one(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    match *pattern.get(p)? {
+        '?' => Some(p + 1),
+        '[' => match match_bracket(&pattern[p + 1..]) {
+            Some((set, after)) => set.contains(c).then_some(pattern.len() - after.len()),
+            // An unterminated bracket is an ordinary character
+            None => (c == '[').then_some(p + 1),
+        },
+        '\\' if p + 1 < pattern.len() => (pattern[p + 1] == c).then_some(p + 2),
+        other => (other == c).then_some(p + 1),
+    }
+}
+
+/// The characters accepted by a `[...]` expression
+struct CharSet {
+    negated: bool,
+    items: Vec<(char, char)>,
+}
+
+impl CharSet {
+    fn contains(&self, c: char) -> bool {
+        self.items.iter().any(|&(low, high)| low <= c && c <= high) != self.negated
+    }
+}
+
+/// Parse a bracket expression after its `[`, returning it with the rest of the pattern
+fn match_bracket(pattern: &[char]) -> Option<(CharSet, &[char])> {
+    let mut pos = 0;
+    let negated = matches!(pattern.first(), Some('!' | '^'));
+    if negated {
+        pos += 1;
+    }
+
+    let mut items = Vec::new();
+    let mut first = true;
+
+    loop {
+        let mut c = *pattern.get(pos)?;
+
+        // A `]` right after the opening bracket is part of the set
+        if c == ']' && !first {
+            return Some((CharSet { negated, items }, &pattern[pos + 1..]));
+        }
+        first = false;
+
+        if c == '\\' {
+            pos += 1;
+            c = *pattern.get(pos)?;
+        }
+
+        if pattern.get(pos + 1) == Some(&'-') && pattern.get(pos + 2).is_some_and(|&end| end != ']')
+        {
+            items.push((c, pattern[pos + 2]));
+            pos += 3;
+        } else {
+            items.push((c, c));
+            pos += 1;
+        }
+    }
+}
+
+/// Quote the characters a pattern treats specially
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Whether a pattern contains an unquoted `*`, `?` or a `[` closed by its `]`
+pub fn has_wildcards(pattern: &str) -> bool {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut pos = 0;
+
+    while let Some(&c) = chars.get(pos) {
+        match c {
+            '\\' => pos += 1,
+            '*' | '?' => return true,
+            // An unterminated bracket is an ordinary character, as in the `[` command
+            '[' if match_bracket(&chars[pos + 1..]).is_some() => return true,
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    false
+}
+
+/// Remove the backslashes a pattern uses for quoting
+pub fn unescape(pattern: &str) -> String {
+    let mut text = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.extend(chars.next()),
+            _ => text.push(c),
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_literals_and_question_marks() {
+        assert!(matches("abc", "abc"));
+        assert!(!matches("abc", "abcd"));
+        assert!(matches("a?c", "abc"));
+        assert!(!matches("a?c", "ac"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn matches_stars() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "abc"));
+        assert!(matches("a*", "a"));
+        assert!(matches("*.rs", "main.rs"));
+        assert!(!matches("*.rs", "main.rst"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(matches("a**c", "abc"));
+        assert!(!matches("a*b", "aXbc"));
+    }
+
+    #[test]
+    fn matches_brackets() {
+        assert!(matches("[ab]x", "bx"));
+        assert!(!matches("[ab]x", "cx"));
+        assert!(matches("[!ab]", "c"));
+        assert!(matches("[^ab]", "c"));
+        assert!(!matches("[!ab]", "a"));
+        assert!(matches("[a-c]", "b"));
+        assert!(matches("[]]", "]"));
+        assert!(matches("[a-]", "-"));
+        assert!(matches("*[0-9]", "file7"));
+    }
+
+    #[test]
+    fn unterminated_bracket_is_literal() {
+        assert!(matches("[", "["));
+        assert!(matches("a[b", "a[b"));
+        assert!(!matches("a[b", "ab"));
+    }
+
+    #[test]
+    fn backslash_quotes_the_next_character() {
+        assert!(matches(r"\*", "*"));
+        assert!(!matches(r"\*", "a"));
+        assert!(matches(r"a\?", "a?"));
+        assert!(matches(r"[\]]", "]"));
+        assert!(matches(&escape("a*[b]"), "a*[b]"));
+    }
+
+    #[test]
+    fn finds_wildcards() {
+        assert!(has_wildcards("*.rs"));
+        assert!(has_wildcards("a?"));
+        assert!(has_wildcards("[ab]"));
+        assert!(has_wildcards("[!a]"));
+        assert!(has_wildcards("x[]]"));
+    }
+
+    #[test]
+    fn unterminated_bracket_is_not_a_wildcard() {
+        assert!(!has_wildcards("["));
+        assert!(!has_wildcards("a[b"));
+        assert!(!has_wildcards("[]"));
+        assert!(!has_wildcards(r"\*\?\[a]"));
+        assert!(!has_wildcards("plain"));
+    }
+
+    #[test]
+    fn pathological_pattern_finishes() {
+        let text = "a".repeat(200);
+
+        assert!(!matches("*a*a*a*a*a*a*a*a*a*a*b", &text));
+        assert!(matches("*a*a*a*a*a*a*a*a*a*a", &text));
+    }
+}
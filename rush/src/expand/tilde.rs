@@ -0,0 +1,56 @@
+use nix::unistd::{User, getuid};
+
+use crate::{
+    parser::ast::{Word, WordPart},
+    vars::read_vars,
+};
+
+/// Replace a leading `~`, `~user`, `~+` or `~-` with the directory it names
+pub fn expand_tilde(word: &Word) -> Word {
+    let Some(WordPart::Literal(first)) = word.parts.first() else {
+        return word.clone();
+    };
+    let Some(rest) = first.strip_prefix('~') else {
+        return word.clone();
+    };
+
+    let (prefix, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+    // Without a slash, the prefix must end the word, as in `~user"x"` it does not
+    if path.is_empty() && word.parts.len() > 1 {
+        return word.clone();
+    }
+
+    let Some(dir) = tilde_dir(prefix) else {
+        return word.clone();
+    };
+
+    let mut parts = vec![WordPart::Quoted(dir)];
+    if !path.is_empty() {
+        parts.push(WordPart::Literal(path.to_string()));
+    }
+    parts.extend(word.parts[1..].iter().cloned());
+
+    Word { parts }
+}
+
+fn tilde_dir(prefix: &str) -> Option<String> {
+    let var = |name: &str| {
+        read_vars()
+            .ok()
+            .and_then(|vars| vars.get(name).map(str::to_string))
+    };
+
+    match prefix {
+        "" => var("HOME").or_else(|| {
+            let user = User::from_uid(getuid()).ok()??;
+            Some(user.dir.to_string_lossy().into_owned())
+        }),
+        "+" => var("PWD"),
+        "-" => var("OLDPWD"),
+        user => {
+            let user = User::from_name(user).ok()??;
+            Some(user.dir.to_string_lossy().into_owned())
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::parser::ast::Command;
+
+static FUNCTIONS: OnceLock<RwLock<FunctionTable>> = OnceLock::new();
+
+/// Functions defined by the user, by name
+#[derive(Default)]
+pub struct FunctionTable {
+    functions: HashMap<String, Arc<Command>>,
+}
+
+impl FunctionTable {
+    /// The body of a function, shared so that it can redefine itself while running
+    pub fn get(&self, name: &str) -> Option<Arc<Command>> {
+        self.functions.get(name).cloned()
+    }
+
+    /// Define a function, replacing any previous one with the same name
+    pub fn define(&mut self, name: &str, body: Command) {
+        self.functions.insert(name.to_string(), Arc::new(body));
+    }
+
+    /// Remove a function, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.functions.remove(name).is_some()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+}
+
+fn function_table() -> &'static RwLock<FunctionTable> {
+    FUNCTIONS.get_or_init(|| RwLock::new(FunctionTable::default()))
+}
+
+pub fn read_functions() -> anyhow::Result<RwLockReadGuard<'static, FunctionTable>> {
+    function_table()
+        .read()
+        .map_err(|_| anyhow::anyhow!("FUNCTIONS read lock poisoned"))
+}
+
+pub fn write_functions() -> anyhow::Result<RwLockWriteGuard<'static, FunctionTable>> {
+    function_table()
+        .write()
+        .map_err(|_| anyhow::anyhow!("FUNCTIONS write lock poisoned"))
+}
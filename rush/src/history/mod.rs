@@ -0,0 +1,295 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Seek, Write},
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
+};
+
+use anyhow::Context;
+use jiff::Timestamp;
+use log::warn;
+use rustyline::history::{FileHistory, History as _};
+use serde::{Deserialize, Serialize};
+
+use crate::vars::read_vars;
+
+/// Name of the history store in the user data directory, one JSON entry per line
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+/// Name of the plain history of earlier versions in the user cache directory
+pub const LEGACY_HISTORY_FILE_NAME: &str = ".history";
+
+static HISTORY: OnceLock<RwLock<History>> = OnceLock::new();
+
+/// A command entered at the prompt and how it ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub command: String,
+    /// When the command was entered, in seconds since the Unix epoch
+    pub time: i64,
+    /// Directory the command was entered in
+    pub cwd: PathBuf,
+    /// Exit status of the command
+    pub status: u8,
+    /// How long the command ran, in milliseconds
+    pub duration_ms: u64,
+}
+
+impl HistoryEntry {
+    /// An entry for a command about to run, its status and duration are set once it ran
+    pub fn new(command: &str, time: Timestamp, cwd: PathBuf) -> Self {
+        Self {
+            command: command.to_string(),
+            time: time.as_second(),
+            cwd,
+            status: 0,
+            duration_ms: 0,
+        }
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        Timestamp::from_second(self.time).unwrap_or(Timestamp::UNIX_EPOCH)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    /// The entry as a line of the history store
+    ///
+    /// The line is as long as it would be with the largest status and duration, so that
+    /// finishing the entry rewrites it in place, whatever other shells appended after it.
+    fn to_line(&self) -> serde_json::Result<String> {
+        let widest = Self {
+            status: u8::MAX,
+            duration_ms: u64::MAX,
+            ..self.clone()
+        };
+        let width = serde_json::to_string(&widest)?.len();
+
+        Ok(format!("{:width$}\n", serde_json::to_string(self)?))
+    }
+}
+
+/// An entry added to the history, to be finished once its command ran
+#[derive(Debug, Clone, Copy)]
+pub struct AddedEntry {
+    index: usize,
+    /// Where the entry starts in the history store
+    offset: Option<u64>,
+}
+
+/// Commands entered at the prompt, oldest first, kept in sync with the history store
+#[derive(Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    /// Store new entries are appended to
+    file: Option<PathBuf>,
+}
+
+impl History {
+    /// Read the entries of a history store, which becomes the one new entries go to
+    ///
+    /// Lines that cannot be read as an entry are skipped, so that a damaged line does not
+    /// lose the rest of the history.
+    pub fn load(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.file = Some(path.to_path_buf());
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("{}", path.display())),
+        };
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+
+            match serde_json::from_str(&line) {
+                Ok(entry) => self.entries.push(entry),
+                Err(e) => warn!(
+                    "{}:{}: skipped history entry: {}",
+                    path.display(),
+                    index + 1,
+                    e
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add the entries of the plain history of earlier versions, unless there is a history
+    /// store already
+    ///
+    /// The plain history kept only commands, so the entries get the time it was last written.
+    pub fn import_legacy(&mut self, legacy: &Path) -> anyhow::Result<()> {
+        if self.file.as_ref().is_none_or(|path| path.exists()) || !legacy.exists() {
+            return Ok(());
+        }
+
+        let mut commands = FileHistory::new();
+        commands.set_max_len(usize::MAX)?;
+        commands
+            .load(legacy)
+            .with_context(|| format!("{}", legacy.display()))?;
+
+        let time = fs::metadata(legacy)?
+            .modified()
+            .ok()
+            .and_then(|modified| Timestamp::try_from(modified).ok())
+            .unwrap_or(Timestamp::UNIX_EPOCH);
+
+        for command in commands.iter() {
+            self.add(HistoryEntry::new(command, time, PathBuf::new()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Add an entry, appending it to the history store
+    pub fn add(&mut self, entry: HistoryEntry) -> anyhow::Result<AddedEntry> {
+        let mut offset = None;
+
+        if let Some(path) = &self.file {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("{}", path.display()))?;
+
+            // Written at once, so that the line ends where the file position is left
+            let line = entry.to_line()?;
+            file.write_all(line.as_bytes())?;
+            offset = Some(file.stream_position()? - line.len() as u64);
+        }
+
+        self.entries.push(entry);
+        Ok(AddedEntry {
+            index: self.entries.len() - 1,
+            offset,
+        })
+    }
+
+    /// Set how the command of an added entry ran, in the history and its store
+    pub fn finish(
+        &mut self,
+        added: AddedEntry,
+        status: u8,
+        duration: Duration,
+    ) -> anyhow::Result<()> {
+        let Some(entry) = self.entries.get_mut(added.index) else {
+            return Ok(());
+        };
+
+        entry.status = status;
+        entry.duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+        if let (Some(path), Some(offset)) = (&self.file, added.offset) {
+            let file = OpenOptions::new()
+                .write(true)
+                .open(path)
+                .with_context(|| format!("{}", path.display()))?;
+
+            file.write_all_at(entry.to_line()?.as_bytes(), offset)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+}
+
+/// The directory commands are recorded in, the logical one `cd` keeps in `$PWD`
+pub fn current_dir() -> PathBuf {
+    read_vars()
+        .ok()
+        .and_then(|vars| vars.get("PWD").map(PathBuf::from))
+        .filter(|pwd| pwd.is_absolute())
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+fn history() -> &'static RwLock<History> {
+    HISTORY.get_or_init(|| RwLock::new(History::default()))
+}
+
+pub fn read_history() -> anyhow::Result<RwLockReadGuard<'static, History>> {
+    history()
+        .read()
+        .map_err(|_| anyhow::anyhow!("HISTORY read lock poisoned"))
+}
+
+pub fn write_history() -> anyhow::Result<RwLockWriteGuard<'static, History>> {
+    history()
+        .write()
+        .map_err(|_| anyhow::anyhow!("HISTORY write lock poisoned"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rush-history-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn commands(history: &History) -> Vec<&str> {
+        history
+            .entries()
+            .iter()
+            .map(|entry| entry.command.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn finishes_entries_in_place() {
+        let dir = temp_dir("finish");
+        let path = dir.join(HISTORY_FILE_NAME);
+        let entry = |command| HistoryEntry::new(command, Timestamp::now(), dir.clone());
+
+        let mut history = History::default();
+        history.load(&path).unwrap();
+        let first = history.add(entry("sleep 1")).unwrap();
+        let second = history.add(entry("false")).unwrap();
+        history.finish(second, 1, Duration::ZERO).unwrap();
+        history
+            .finish(first, 0, Duration::from_millis(1234))
+            .unwrap();
+
+        let mut loaded = History::default();
+        loaded.load(&path).unwrap();
+
+        assert_eq!(commands(&loaded), ["sleep 1", "false"]);
+        assert_eq!(loaded.entries()[0].duration_ms, 1234);
+        assert_eq!(loaded.entries()[1].status, 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn imports_legacy_history_once() {
+        let dir = temp_dir("legacy");
+        let path = dir.join(HISTORY_FILE_NAME);
+        let legacy = dir.join(LEGACY_HISTORY_FILE_NAME);
+        fs::write(&legacy, "#V2\necho \"a\\nb\"\nls\\\\\n").unwrap();
+
+        let mut history = History::default();
+        history.load(&path).unwrap();
+        history.import_legacy(&legacy).unwrap();
+        assert_eq!(commands(&history), ["echo \"a\nb\"", "ls\\"]);
+
+        let mut reloaded = History::default();
+        reloaded.load(&path).unwrap();
+        reloaded.import_legacy(&legacy).unwrap();
+        assert_eq!(commands(&reloaded), ["echo \"a\nb\"", "ls\\"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
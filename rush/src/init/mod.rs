@@ -46,9 +46,10 @@ define_user_dir!(
     "User cache directory"
 );
 
-pub fn init_module() -> anyhow::Result<()> {
-    // Init logger
-    Builder::from_env(Env::default().default_filter_or("info")).init();
+pub fn init_module(interactive: bool) -> anyhow::Result<()> {
+    // Init logger, scripts only log problems since their stderr is part of their output
+    let default_filter = if interactive { "info" } else { "warn" };
+    Builder::from_env(Env::default().default_filter_or(default_filter)).init();
 
     let dirs = [
         get_user_data_dir()?,
@@ -0,0 +1,142 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, RepeatCount};
+
+static ABBREVIATIONS: OnceLock<RwLock<AbbrTable>> = OnceLock::new();
+
+/// Abbreviations by name, expanded in the line editor before the line is run
+#[derive(Default)]
+pub struct AbbrTable {
+    abbrs: BTreeMap<String, String>,
+}
+
+impl AbbrTable {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.abbrs.get(name).map(String::as_str)
+    }
+
+    pub fn define(&mut self, name: &str, expansion: &str) {
+        self.abbrs.insert(name.to_string(), expansion.to_string());
+    }
+
+    /// Remove an abbreviation, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.abbrs.remove(name).is_some()
+    }
+
+    /// Abbreviations and their expansions, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.abbrs
+            .iter()
+            .map(|(name, expansion)| (name.as_str(), expansion.as_str()))
+    }
+}
+
+fn abbr_table() -> &'static RwLock<AbbrTable> {
+    ABBREVIATIONS.get_or_init(|| RwLock::new(AbbrTable::default()))
+}
+
+pub fn read_abbrs() -> anyhow::Result<RwLockReadGuard<'static, AbbrTable>> {
+    abbr_table()
+        .read()
+        .map_err(|_| anyhow::anyhow!("ABBREVIATIONS read lock poisoned"))
+}
+
+pub fn write_abbrs() -> anyhow::Result<RwLockWriteGuard<'static, AbbrTable>> {
+    abbr_table()
+        .write()
+        .map_err(|_| anyhow::anyhow!("ABBREVIATIONS write lock poisoned"))
+}
+
+/// The abbreviation ending at `pos`, as the byte offset where it starts and its expansion
+///
+/// Only command names are expanded, that is words at the start of the line or after
+/// `|`, `&`, `;` or `(`.
+fn abbr_at(line: &str, pos: usize) -> Option<(usize, String)> {
+    let before = &line[..pos];
+
+    if line[pos..].starts_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace() || matches!(c, '|' | '&' | ';' | '('))
+        .map_or(0, |(index, c)| index + c.len_utf8());
+
+    let preceding = before[..start].trim_end();
+    if !(preceding.is_empty() || preceding.ends_with(['|', '&', ';', '('])) {
+        return None;
+    }
+
+    let expansion = read_abbrs().ok()?.get(&before[start..])?.to_string();
+    Some((start, expansion))
+}
+
+/// Expand the abbreviation at the end of an accepted line, so that history records it in full
+pub(super) fn expand_line(line: String) -> String {
+    match abbr_at(&line, line.trim_end().len()) {
+        Some((start, expansion)) => {
+            format!("{}{}", &line[..start], expansion)
+        }
+        None => line,
+    }
+}
+
+/// Set by the space key handler, the completion it triggers then expands the abbreviation
+static EXPANSION_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// The expansion requested by the space key handler, with the space itself
+///
+/// Completion replaces text and moves the cursor past it in one step, which no editing
+/// command does.
+pub(super) fn take_expansion(line: &str, pos: usize) -> Option<(usize, String)> {
+    if !EXPANSION_REQUESTED.swap(false, Ordering::Relaxed) {
+        return None;
+    }
+
+    abbr_at(line, pos).map(|(start, expansion)| (start, format!("{expansion} ")))
+}
+
+/// Expands the abbreviation before the cursor when space is pressed
+pub(super) struct AbbrHandler;
+
+impl ConditionalEventHandler for AbbrHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        abbr_at(ctx.line(), ctx.pos())?;
+        EXPANSION_REQUESTED.store(true, Ordering::Relaxed);
+
+        Some(Cmd::Complete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_command_names_only() {
+        write_abbrs().unwrap().define("gs", "git status");
+
+        assert_eq!(abbr_at("gs", 2), Some((0, "git status".to_string())));
+        assert_eq!(abbr_at("ls | gs", 7), Some((5, "git status".to_string())));
+        assert_eq!(abbr_at("echo gs", 7), None);
+        assert_eq!(abbr_at("gsx", 2), None);
+    }
+
+    #[test]
+    fn handles_multibyte_separators() {
+        write_abbrs().unwrap().define("gd", "git diff");
+
+        assert_eq!(abbr_at("echo \u{a0}x", 8), None);
+        assert_eq!(abbr_at("\u{a0}gd", 4), Some((2, "git diff".to_string())));
+        assert_eq!(abbr_at("\u{3000}gd", 5), Some((3, "git diff".to_string())));
+    }
+}
@@ -0,0 +1,352 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use abi_stable::std_types::{RString, RVec};
+use rustyline::completion::Pair;
+
+use super::{COMMAND_PREFIXES, is_assignment};
+
+use crate::{
+    aliases::read_aliases,
+    executor::{is_executable, path_executables},
+    functions::read_functions,
+    plugin::{get_plugin, is_plugin, plugin_names},
+    shell_builtins::builtins_registry,
+    vars::read_vars,
+};
+
+/// Sub-commands of the `plugin` builtin, which take a plugin name
+const PLUGIN_SUB_COMMANDS: [&str; 3] = ["desc", "help", "version"];
+
+/// Characters escaped with a backslash when a completion is inserted
+const SPECIAL_CHARS: &str = " \t\n\\'\"$`&|;()<>*?[]{}#!";
+
+/// The word being completed, as read from the start of the line up to the cursor
+#[derive(Default)]
+struct WordAt {
+    /// Byte offset where the word starts
+    start: usize,
+    /// The word without its quotes and escapes
+    text: String,
+    /// Quote left open before the cursor
+    quote: Option<char>,
+    /// Words of the command before this one, without their quotes and escapes
+    words: Vec<String>,
+    /// Whether the word is the target of a redirection
+    redirect: bool,
+}
+
+impl WordAt {
+    fn read(line: &str) -> Self {
+        let mut word = WordAt::default();
+        let mut chars = line.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            let next = index + c.len_utf8();
+
+            match (word.quote, c) {
+                (Some(quote), _) if c == quote => word.quote = None,
+                (Some('"'), '\\')
+                    if chars
+                        .peek()
+                        .is_some_and(|(_, escaped)| matches!(escaped, '$' | '`' | '"' | '\\')) =>
+                {
+                    word.text.extend(chars.next().map(|(_, escaped)| escaped));
+                }
+                (Some(_), _) => word.text.push(c),
+                (None, '\\') => word.text.extend(chars.next().map(|(_, escaped)| escaped)),
+                (None, '\'' | '"') => word.quote = Some(c),
+                (None, '<' | '>') => {
+                    // A descriptor number is part of the operator, as in `2>`
+                    if word.text.chars().all(|c| c.is_ascii_digit()) {
+                        word.text.clear();
+                    }
+                    word.end(next);
+                    word.redirect = true;
+                }
+                // `>&` and `&>` are redirections rather than a background command
+                (None, '&') if line[..index].ends_with(['<', '>']) => word.start = next,
+                (None, '&') if chars.peek().is_some_and(|(_, c)| *c == '>') => {
+                    word.end(next);
+                }
+                (None, '|' | '&' | ';' | '(' | ')') => {
+                    word.end(next);
+                    word.words.clear();
+                    word.redirect = false;
+                }
+                (None, c) if c.is_whitespace() => word.end(next),
+                (None, c) => word.text.push(c),
+            }
+        }
+
+        word
+    }
+
+    /// End the current word, the next one starting at `start`
+    fn end(&mut self, start: usize) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+
+            // The target of a redirection is not an argument
+            if !std::mem::take(&mut self.redirect) {
+                self.words.push(text);
+            }
+        }
+
+        self.start = start;
+    }
+
+    /// The command name and arguments before the word, without leading assignments and keywords
+    fn command(&self) -> &[String] {
+        let skipped = self
+            .words
+            .iter()
+            .take_while(|word| COMMAND_PREFIXES.contains(&word.as_str()) || is_assignment(word))
+            .count();
+
+        &self.words[skipped..]
+    }
+
+    fn is_command_name(&self) -> bool {
+        !self.redirect && self.command().is_empty() && !is_assignment(&self.text)
+    }
+
+    /// Insert `text` in place of the word, completing it when `finished`
+    fn replacement(&self, text: &str, finished: bool) -> String {
+        match self.quote {
+            Some(quote) if finished => format!("{quote}{text}{quote} "),
+            Some(quote) => format!("{quote}{text}"),
+            None if finished => format!("{} ", escape(text)),
+            None => escape(text),
+        }
+    }
+}
+
+/// Candidates replacing the text from the returned offset to the cursor
+pub(super) fn complete(line: &str, pos: usize) -> (usize, Vec<Pair>) {
+    let line = &line[..pos];
+    let word = WordAt::read(line);
+
+    if let Some((start, candidates)) = complete_variable(line, &word) {
+        return (start, candidates);
+    }
+
+    let candidates = if word.is_command_name() && !word.text.contains('/') {
+        complete_command(&word)
+    } else {
+        complete_argument(&word)
+    };
+
+    (word.start, candidates)
+}
+
+fn complete_command(word: &WordAt) -> Vec<Pair> {
+    let mut names = path_executables();
+
+    if let Ok(registry) = builtins_registry() {
+        names.extend(registry.names().map(str::to_string));
+    }
+    if let Ok(functions) = read_functions() {
+        names.extend(functions.names().map(str::to_string));
+    }
+    if let Ok(aliases) = read_aliases() {
+        names.extend(aliases.iter().map(|(name, _)| name.to_string()));
+    }
+    names.extend(plugin_names());
+
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| name.starts_with(&word.text))
+        .map(|name| Pair {
+            replacement: word.replacement(&name, true),
+            display: name,
+        })
+        .collect()
+}
+
+fn complete_argument(word: &WordAt) -> Vec<Pair> {
+    match word.command() {
+        [name] if !word.redirect && name == "plugin" => {
+            complete_words(word, PLUGIN_SUB_COMMANDS.iter().map(|sub| sub.to_string()))
+        }
+        [name, sub] if !word.redirect && name == "plugin" && is_plugin_sub_command(sub) => {
+            complete_words(word, plugin_names())
+        }
+        [name, args @ ..] if !word.redirect => match plugin_candidates(name, args, &word.text) {
+            Some(candidates) => complete_words(word, candidates),
+            None => complete_path(word),
+        },
+        _ => complete_path(word),
+    }
+}
+
+/// Candidates a plugin command offers for its argument, `None` when it does not complete them
+fn plugin_candidates(name: &str, args: &[String], text: &str) -> Option<Vec<String>> {
+    // Builtins and functions run instead of a plugin with the same name
+    if !is_plugin(name)
+        || builtins_registry().is_ok_and(|registry| registry.contains(name))
+        || read_functions().is_ok_and(|functions| functions.get(name).is_some())
+    {
+        return None;
+    }
+
+    let complete = get_plugin(name).ok()?.complete()?;
+
+    let args: RVec<RString> = args
+        .iter()
+        .map(String::as_str)
+        .chain([text])
+        .map(RString::from)
+        .collect();
+    let cursor = args.len() - 1;
+
+    Some(
+        complete(args, cursor)
+            .into_iter()
+            .map(RString::into_string)
+            .collect(),
+    )
+}
+
+fn is_plugin_sub_command(name: &str) -> bool {
+    PLUGIN_SUB_COMMANDS.contains(&name) || name == "description"
+}
+
+/// Candidates among fixed words, sorted
+fn complete_words(word: &WordAt, words: impl IntoIterator<Item = String>) -> Vec<Pair> {
+    let mut words: Vec<String> = words
+        .into_iter()
+        .filter(|name| name.starts_with(&word.text))
+        .collect();
+    words.sort();
+
+    words
+        .into_iter()
+        .map(|name| Pair {
+            replacement: word.replacement(&name, true),
+            display: name,
+        })
+        .collect()
+}
+
+/// Files in the directory the word names, directories ending with `/`
+///
+/// Only executables and directories are offered for a command name given as a path.
+fn complete_path(word: &WordAt) -> Vec<Pair> {
+    let (dir, prefix) = match word.text.rfind('/') {
+        Some(index) => word.text.split_at(index + 1),
+        None => ("", word.text.as_str()),
+    };
+
+    let Ok(entries) = fs::read_dir(expand_home(dir)) else {
+        return Vec::new();
+    };
+
+    let executables_only = word.is_command_name();
+    let mut candidates = BTreeMap::new();
+
+    for entry in entries.flatten() {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+
+        // Hidden files are only offered once their dot is typed
+        if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+            continue;
+        }
+
+        let path = entry.path();
+        let is_dir = path.is_dir();
+
+        if executables_only && !is_dir && !is_executable(&path) {
+            continue;
+        }
+
+        let text = format!("{dir}{name}");
+        let pair = if is_dir {
+            Pair {
+                replacement: word.replacement(&format!("{text}/"), false),
+                display: format!("{name}/"),
+            }
+        } else {
+            Pair {
+                replacement: word.replacement(&text, true),
+                display: name.clone(),
+            }
+        };
+
+        candidates.insert(name, pair);
+    }
+
+    candidates.into_values().collect()
+}
+
+/// Variable names for a `$NAME` or `${NAME` before the cursor
+fn complete_variable(line: &str, word: &WordAt) -> Option<(usize, Vec<Pair>)> {
+    if word.quote == Some('\'') {
+        return None;
+    }
+
+    let dollar = line.rfind('$')?;
+    let after = &line[dollar + 1..];
+    let (braced, name) = match after.strip_prefix('{') {
+        Some(name) => (true, name),
+        None => (false, after),
+    };
+
+    if dollar < word.start || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    let mut names: Vec<String> = read_vars()
+        .ok()?
+        .names()
+        .filter(|var| var.starts_with(name))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+
+    let candidates = names
+        .into_iter()
+        .map(|var| Pair {
+            replacement: if braced {
+                format!("{var}}}")
+            } else {
+                var.clone()
+            },
+            display: var,
+        })
+        .collect();
+
+    Some((line.len() - name.len(), candidates))
+}
+
+/// The directory to list for the directory part of a word, `~/` standing for `$HOME`
+fn expand_home(dir: &str) -> PathBuf {
+    if dir.is_empty() {
+        return PathBuf::from(".");
+    }
+
+    match dir.strip_prefix("~/") {
+        Some(rest) => env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(rest),
+        None => PathBuf::from(dir),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if SPECIAL_CHARS.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
@@ -0,0 +1,377 @@
+use colored::Colorize;
+
+use super::{COMMAND_PREFIXES, abbr::read_abbrs, is_assignment, multiline};
+use crate::{
+    aliases::read_aliases, executor::find_executable, functions::read_functions, plugin::is_plugin,
+    shell_builtins::builtins_registry,
+};
+
+/// Reserved words after which the next word is not a command, as `for name`
+const ARGUMENT_KEYWORDS: [&str; 7] = ["for", "case", "function", "fi", "done", "esac", "}"];
+
+/// Control operators, longest first
+const OPERATORS: [&str; 8] = ["||", "&&", ";;", "|", "&", ";", "(", ")"];
+
+/// Redirection operators after an optional descriptor number, longest first
+const REDIRECTIONS: [&str; 7] = ["&>>", "&>", ">>", ">&", "<&", ">", "<"];
+
+/// What the next word of the line is
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Command,
+    Argument,
+}
+
+/// The line with ANSI colours, the command word green when it can be run and red otherwise
+pub(super) fn highlight(line: &str) -> String {
+    let pending = multiline::pending();
+    if pending.is_empty() {
+        return highlight_input(line);
+    }
+
+    // A continuation line is coloured as part of the input it continues, as a quoted string
+    let input = format!("{pending}\n{line}");
+    skip_visible(&highlight_input(&input), pending.chars().count() + 1)
+}
+
+fn highlight_input(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() * 2);
+    let mut expect = Expect::Command;
+    let mut pos = 0;
+
+    while let Some(c) = line[pos..].chars().next() {
+        let rest = &line[pos..];
+
+        let len = if c == '\n' {
+            out.push(c);
+            expect = Expect::Command;
+            1
+        } else if c.is_whitespace() {
+            out.push(c);
+            c.len_utf8()
+        } else if c == '#' {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            out.push_str(&rest[..len].bright_black().to_string());
+            len
+        } else if let Some(len) = redirection_len(rest) {
+            out.push_str(&rest[..len].blue().to_string());
+            len
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            out.push_str(&op.magenta().to_string());
+            expect = Expect::Command;
+            op.len()
+        } else {
+            let len = word_len(rest);
+            let word = &rest[..len];
+
+            if expect == Expect::Command {
+                out.push_str(&highlight_command(word));
+                expect = next_expect(word);
+            } else {
+                out.push_str(&highlight_word(word));
+            }
+            len
+        };
+
+        pos += len;
+    }
+
+    out
+}
+
+/// The coloured text after its first `count` visible characters, opening with the colour
+/// in effect there
+fn skip_visible(text: &str, count: usize) -> String {
+    let mut colour = String::new();
+    let mut visible = 0;
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        if c == '\x1b' {
+            let len = text[index..]
+                .find(|c: char| c.is_ascii_alphabetic())
+                .map_or(text.len() - index, |end| end + 1);
+            let code = &text[index..index + len];
+            colour = if code == "\x1b[0m" {
+                String::new()
+            } else {
+                code.to_string()
+            };
+
+            for _ in code.chars().skip(1) {
+                chars.next();
+            }
+            continue;
+        }
+
+        if visible == count {
+            return format!("{colour}{}", &text[index..]);
+        }
+        visible += 1;
+    }
+
+    String::new()
+}
+
+/// Length of a redirection operator at the start of the text, with its descriptor number
+fn redirection_len(text: &str) -> Option<usize> {
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+
+    let op = REDIRECTIONS
+        .iter()
+        .find(|op| text[digits..].starts_with(**op))?;
+
+    (digits == 0 || !op.starts_with('&')).then_some(digits + op.len())
+}
+
+/// Length of the word at the start of the text, which ends at an unquoted blank or operator
+fn word_len(text: &str) -> usize {
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        if c.is_whitespace() || "|&;<>()".contains(c) {
+            break;
+        }
+
+        pos += match c {
+            '\\' => escape_len(&text[pos..]),
+            '\'' | '"' | '`' => quoted_len(&text[pos..]),
+            '$' => dollar_len(&text[pos..]),
+            _ => c.len_utf8(),
+        };
+    }
+
+    pos
+}
+
+/// Length of a backslash and the character it escapes
+fn escape_len(text: &str) -> usize {
+    1 + text[1..].chars().next().map_or(0, char::len_utf8)
+}
+
+/// Length of a quoted string starting at its opening quote, up to the end of the text if unclosed
+fn quoted_len(text: &str) -> usize {
+    let quote = text.chars().next().unwrap_or_default();
+    let mut chars = text.char_indices().skip(1);
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if quote != '\'' => {
+                chars.next();
+            }
+            '$' if quote == '"' => {
+                let len = dollar_len(&text[index..]);
+                // Skip what the expansion covers, it may contain quotes
+                for _ in text[index + 1..index + len].chars() {
+                    chars.next();
+                }
+            }
+            _ if c == quote => return index + 1,
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
+/// Length of an expansion starting at `$`, up to the end of the text if unclosed
+fn dollar_len(text: &str) -> usize {
+    let rest = &text[1..];
+
+    match rest.chars().next() {
+        Some('{') => closing_len(rest, '{', '}') + 1,
+        Some('(') => closing_len(rest, '(', ')') + 1,
+        Some(c) if c.is_ascii_digit() || "?$#@*!-".contains(c) => 2,
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            1 + rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len())
+        }
+        _ => 1,
+    }
+}
+
+/// Length up to the bracket closing the one at the start of the text, counting nested ones
+fn closing_len(text: &str, open: char, close: char) -> usize {
+    let mut depth = 0;
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\'' | '"' => {
+                let len = quoted_len(&text[index..]);
+                for _ in text[index + 1..index + len].chars() {
+                    chars.next();
+                }
+            }
+            _ if c == open => depth += 1,
+            _ if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
+/// Colour the quoted strings and expansions of a word
+fn highlight_word(word: &str) -> String {
+    let mut out = String::with_capacity(word.len() * 2);
+    let mut pos = 0;
+
+    while let Some(c) = word[pos..].chars().next() {
+        let rest = &word[pos..];
+
+        let len = match c {
+            '\\' => {
+                let len = escape_len(rest);
+                out.push_str(&rest[..len]);
+                len
+            }
+            '\'' => {
+                let len = quoted_len(rest);
+                out.push_str(&rest[..len].yellow().to_string());
+                len
+            }
+            '"' => {
+                let len = quoted_len(rest);
+                out.push_str(&highlight_double_quoted(&rest[..len]));
+                len
+            }
+            '`' => {
+                let len = quoted_len(rest);
+                out.push_str(&rest[..len].cyan().to_string());
+                len
+            }
+            '$' if dollar_len(rest) > 1 => {
+                let len = dollar_len(rest);
+                out.push_str(&rest[..len].cyan().to_string());
+                len
+            }
+            _ => {
+                out.push(c);
+                c.len_utf8()
+            }
+        };
+
+        pos += len;
+    }
+
+    out
+}
+
+/// Colour a double quoted string, with the expansions inside it coloured as such
+fn highlight_double_quoted(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut literal_start = 0;
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        match c {
+            '\\' => pos += escape_len(&text[pos..]),
+            '$' if dollar_len(&text[pos..]) > 1 => {
+                let len = dollar_len(&text[pos..]);
+                if literal_start < pos {
+                    out.push_str(&text[literal_start..pos].yellow().to_string());
+                }
+                out.push_str(&text[pos..pos + len].cyan().to_string());
+                pos += len;
+                literal_start = pos;
+            }
+            _ => pos += c.len_utf8(),
+        }
+    }
+
+    if literal_start < text.len() {
+        out.push_str(&text[literal_start..].yellow().to_string());
+    }
+    out
+}
+
+/// Colour a word in command position, which may be an assignment or a reserved word
+fn highlight_command(word: &str) -> String {
+    if is_assignment(word) || is_keyword(word) {
+        return highlight_word(word);
+    }
+
+    // Expansions are only known when the command runs
+    if word.contains(['$', '`']) {
+        return highlight_word(word);
+    }
+
+    if is_command(&unquote(word)) {
+        word.green().to_string()
+    } else {
+        word.red().to_string()
+    }
+}
+
+/// What follows a word in command position
+fn next_expect(word: &str) -> Expect {
+    if is_assignment(word) || COMMAND_PREFIXES.contains(&word) {
+        Expect::Command
+    } else {
+        Expect::Argument
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    COMMAND_PREFIXES.contains(&word) || ARGUMENT_KEYWORDS.contains(&word)
+}
+
+/// Whether the name runs something, in the order the executor looks it up
+fn is_command(name: &str) -> bool {
+    builtins_registry().is_ok_and(|registry| registry.contains(name))
+        || read_functions().is_ok_and(|functions| functions.get(name).is_some())
+        || read_aliases().is_ok_and(|aliases| aliases.get(name).is_some())
+        || read_abbrs().is_ok_and(|abbrs| abbrs.get(name).is_some())
+        || is_plugin(name)
+        || find_executable(name).is_some()
+}
+
+/// The word without its quotes and escapes
+fn unquote(word: &str) -> String {
+    let mut text = String::with_capacity(word.len());
+    let mut quote = None;
+    let mut chars = word.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some('"') | None, '\\') => text.extend(chars.next()),
+            (Some(_), _) => text.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, _) => text.push(c),
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_visible_characters_only() {
+        let text = "\x1b[32mecho\x1b[0m \x1b[33m\"a\nb\"\x1b[0m";
+
+        assert_eq!(
+            skip_visible(text, 2),
+            "\x1b[32mho\x1b[0m \x1b[33m\"a\nb\"\x1b[0m"
+        );
+        assert_eq!(skip_visible(text, 4), " \x1b[33m\"a\nb\"\x1b[0m");
+        assert_eq!(skip_visible(text, 8), "\x1b[33mb\"\x1b[0m");
+        assert_eq!(skip_visible(text, 10), "");
+        assert_eq!(skip_visible("plain\ntext", 6), "text");
+    }
+}
@@ -0,0 +1,54 @@
+use rustyline::{
+    Cmd, ConditionalEventHandler, Event, EventContext, RepeatCount,
+    history::{History, SearchDirection},
+};
+
+use crate::history::{current_dir, read_history};
+
+/// The rest of the latest history entry starting with the line, entries from the current
+/// directory first
+pub(super) fn suggest(line: &str, pos: usize, history: &dyn History) -> Option<String> {
+    if line.trim().is_empty() || pos < line.len() {
+        return None;
+    }
+
+    let entry = from_directory(line).or_else(|| from_history(line, history))?;
+    Some(entry[line.len()..].to_string())
+}
+
+fn from_directory(line: &str) -> Option<String> {
+    let cwd = current_dir();
+
+    read_history()
+        .ok()?
+        .entries()
+        .iter()
+        .rev()
+        .find(|entry| {
+            entry.cwd == cwd && entry.command.len() > line.len() && entry.command.starts_with(line)
+        })
+        .map(|entry| entry.command.clone())
+}
+
+fn from_history(line: &str, history: &dyn History) -> Option<String> {
+    let mut index = history.len().checked_sub(1)?;
+
+    // Entries equal to the line have nothing to suggest, an older one may
+    while let Ok(Some(found)) = history.starts_with(line, index, SearchDirection::Reverse) {
+        if found.entry.len() > line.len() {
+            return Some(found.entry.into_owned());
+        }
+        index = found.idx.checked_sub(1)?;
+    }
+
+    None
+}
+
+/// Accepts the suggestion with End, which otherwise only moves to the end of the line
+pub(super) struct AcceptHintHandler;
+
+impl ConditionalEventHandler for AcceptHintHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        (ctx.has_hint() && ctx.pos() == ctx.line().len()).then_some(Cmd::CompleteHint)
+    }
+}
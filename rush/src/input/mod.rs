@@ -0,0 +1,170 @@
+mod abbr;
+mod complete;
+mod highlight;
+mod hint;
+mod multiline;
+
+use std::{
+    borrow::Cow,
+    io::{Error, ErrorKind},
+    sync::{OnceLock, RwLock, RwLockWriteGuard},
+};
+
+use abbr::AbbrHandler;
+use colored::Colorize;
+use hint::AcceptHintHandler;
+use rustyline::{
+    CompletionType, Config, Context, Editor, EventHandler, Helper, KeyCode, KeyEvent, Modifiers,
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::{CmdKind, Highlighter},
+    hint::Hinter,
+    history::FileHistory,
+    validate::Validator,
+};
+
+use crate::{parser, vars};
+
+pub use abbr::{read_abbrs, write_abbrs};
+
+/// Reserved words followed by a command, as in `if cmd` or `do cmd`
+const COMMAND_PREFIXES: [&str; 8] = ["{", "if", "then", "elif", "else", "while", "until", "do"];
+
+/// Whether the word assigns a variable, as `name=value`
+pub(super) fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .is_some_and(|(name, _)| vars::is_valid_name(name))
+}
+
+type RushEditor = Editor<RushHelper, FileHistory>;
+
+static READLINE: OnceLock<RwLock<RushEditor>> = OnceLock::new();
+
+/// Hooks the line editor calls while a line is typed
+struct RushHelper;
+
+impl Completer for RushHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        // A space typed after an abbreviation is completed into its expansion
+        if let Some((start, expansion)) = abbr::take_expansion(line, pos) {
+            return Ok((
+                start,
+                vec![Pair {
+                    display: expansion.clone(),
+                    replacement: expansion,
+                }],
+            ));
+        }
+
+        Ok(complete::complete(line, pos))
+    }
+}
+
+impl Hinter for RushHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, ctx: &Context<'_>) -> Option<String> {
+        let pending = multiline::pending();
+        if pending.is_empty() {
+            return hint::suggest(line, pos, ctx.history());
+        }
+
+        // A continuation line is suggested what continues the whole input, not a new command
+        let input = format!("{pending}\n{line}");
+        hint::suggest(&input, pending.len() + 1 + pos, ctx.history())
+    }
+}
+
+impl Highlighter for RushHelper {
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        Cow::Owned(highlight::highlight(line))
+    }
+
+    fn highlight_char(&self, _: &str, _: usize, kind: CmdKind) -> bool {
+        // Typing can change how the command word resolves, moving the cursor cannot
+        kind != CmdKind::MoveCursor
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+}
+
+/// Required by `Helper`, incomplete input is continued by `readline` instead
+///
+/// The line editor lays out a multi-line buffer from its raw text, so a `$PS2` drawn at the
+/// start of its later lines would leave the cursor off by the width of the prompt.
+impl Validator for RushHelper {}
+
+impl Helper for RushHelper {}
+
+pub fn init_module() -> anyhow::Result<()> {
+    // Abbreviations expand as soon as the command name is followed by a space
+    write_readline()?.bind_sequence(
+        KeyEvent(KeyCode::Char(' '), Modifiers::NONE),
+        EventHandler::Conditional(Box::new(AbbrHandler)),
+    );
+
+    // Right arrow accepts the suggestion already, End does too as in fish
+    write_readline()?.bind_sequence(
+        KeyEvent(KeyCode::End, Modifiers::NONE),
+        EventHandler::Conditional(Box::new(AcceptHintHandler)),
+    );
+
+    Ok(())
+}
+
+fn get_readline() -> &'static RwLock<RushEditor> {
+    READLINE.get_or_init(|| {
+        // Like other shells, the first Tab completes the common prefix and the second lists
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor = RushEditor::with_config(config).expect("Failed to create readline editor");
+        editor.set_helper(Some(RushHelper));
+        RwLock::new(editor)
+    })
+}
+
+fn write_readline() -> anyhow::Result<RwLockWriteGuard<'static, RushEditor>> {
+    get_readline()
+        .write()
+        .map_err(|_| anyhow::anyhow!("Readline write lock poisoned"))
+}
+
+/// Read a command, continuing it on further lines behind `$PS2` while it is incomplete
+pub fn readline(prompt: &str) -> Result<String, ReadlineError> {
+    let mut editor =
+        write_readline().map_err(|_| ReadlineError::Io(Error::from(ErrorKind::Deadlock)))?;
+    let mut input = editor.readline(prompt).map(abbr::expand_line)?;
+
+    while parser::is_incomplete(&input) {
+        multiline::set_pending(&input);
+        let line = editor.readline(&multiline::ps2());
+        multiline::set_pending("");
+
+        match line {
+            Ok(line) => {
+                input.push('\n');
+                input.push_str(&abbr::expand_line(line));
+            }
+            // The parser reports what the input was missing
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(input)
+}
+
+pub fn add_history(entry: &str) -> anyhow::Result<()> {
+    write_readline()?.add_history_entry(entry)?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::sync::Mutex;
+
+use crate::vars::read_vars;
+
+/// Continuation prompt used when `$PS2` is unset
+const DEFAULT_PS2: &str = "> ";
+
+/// Lines entered so far of an incomplete input, while its continuation lines are read
+static PENDING: Mutex<String> = Mutex::new(String::new());
+
+/// The prompt shown before each continuation line
+pub(super) fn ps2() -> String {
+    read_vars()
+        .ok()
+        .and_then(|vars| vars.get("PS2").map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_PS2.to_string())
+}
+
+/// The lines the line being edited continues, empty when it starts a new input
+pub(super) fn pending() -> String {
+    PENDING
+        .lock()
+        .map(|pending| pending.clone())
+        .unwrap_or_default()
+}
+
+pub(super) fn set_pending(input: &str) {
+    if let Ok(mut pending) = PENDING.lock() {
+        input.clone_into(&mut pending);
+    }
+}
@@ -0,0 +1,343 @@
+mod table;
+
+use std::{
+    io::{Write, stderr, stdin},
+    os::fd::{FromRawFd, OwnedFd},
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use log::{debug, warn};
+use nix::{
+    errno::Errno,
+    fcntl::{FcntlArg, fcntl},
+    libc,
+    sys::{
+        signal::{SaFlags, SigAction, SigHandler, SigSet, Signal, killpg, sigaction, signal},
+        termios::{SetArg, tcgetattr, tcsetattr},
+        wait::{WaitPidFlag, WaitStatus, waitpid},
+    },
+    unistd::{Pid, getpgrp, getpid, isatty, setpgid, tcgetpgrp, tcsetpgrp},
+};
+
+pub use table::{Job, JobState, ProcessState, read_job_table, write_job_table};
+
+/// Signals an interactive shell ignores so that only its jobs can be stopped
+pub const JOB_CONTROL_SIGNALS: [Signal; 3] = [Signal::SIGTSTP, Signal::SIGTTIN, Signal::SIGTTOU];
+
+/// The terminal is kept on a high descriptor, out of the way of user redirections
+const TERMINAL_FD: i32 = 255;
+
+static JOB_CONTROL: AtomicBool = AtomicBool::new(false);
+/// Set by Ctrl-C, at the shell or at a foreground job it waits for, the shell then stops
+/// running loops
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static TERMINAL: OnceLock<Terminal> = OnceLock::new();
+
+struct Terminal {
+    fd: OwnedFd,
+    shell_pgid: Pid,
+    /// Terminal modes the shell gets back after every foreground job
+    tmodes: libc::termios,
+}
+
+pub fn is_job_control_enabled() -> bool {
+    JOB_CONTROL.load(Ordering::Relaxed)
+}
+
+/// Whether a foreground job was interrupted since the last call, clearing the flag
+pub fn take_interrupted() -> bool {
+    INTERRUPTED.swap(false, Ordering::Relaxed)
+}
+
+extern "C" fn on_interrupt(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+/// Catch `SIGINT` instead of dying, so that Ctrl-C stops loops that run no child process,
+/// returning how it was handled before
+pub fn catch_interrupts() -> anyhow::Result<SigAction> {
+    // Interrupted system calls resume, as they did while the signal was ignored
+    let action = SigAction::new(
+        SigHandler::Handler(on_interrupt),
+        SaFlags::SA_RESTART,
+        SigSet::empty(),
+    );
+
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    Ok(unsafe { sigaction(Signal::SIGINT, &action) }?)
+}
+
+/// Handle `SIGINT` as before `catch_interrupts`
+pub fn restore_interrupts(action: &SigAction) -> anyhow::Result<()> {
+    // SAFETY: the action was in place before, its handler was fit to run then
+    unsafe { sigaction(Signal::SIGINT, action) }?;
+    Ok(())
+}
+
+/// Forked children run their commands without managing jobs of their own
+pub fn disable_job_control() {
+    JOB_CONTROL.store(false, Ordering::Relaxed);
+}
+
+/// Move the calling process into the job's process group, taking the terminal for foreground jobs
+///
+/// Runs in children between fork and exec, so it only calls async-signal-safe functions.
+/// Failures are ignored, the parent makes the same calls through `assign_job`.
+pub fn join_job(pgid: Option<Pid>, foreground: bool) {
+    if !is_job_control_enabled() {
+        return;
+    }
+
+    let pid = getpid();
+    let pgid = pgid.unwrap_or(pid);
+    let _ = setpgid(pid, pgid);
+
+    if foreground && let Some(terminal) = TERMINAL.get() {
+        let _ = tcsetpgrp(&terminal.fd, pgid);
+    }
+}
+
+/// Parent side of `join_job`, so neither process races the other
+pub fn assign_job(pid: Pid, pgid: Pid) {
+    if is_job_control_enabled() {
+        // The child may already have exec'd, in which case it did this itself
+        let _ = setpgid(pid, pgid);
+    }
+}
+
+/// Wait for a new foreground job, which owns the terminal until it finishes or stops
+pub fn run_foreground(pgid: Pid, command: String, pids: &[Pid]) -> u8 {
+    wait_foreground(Job::new(pgid, command, pids))
+}
+
+fn wait_foreground(mut job: Job) -> u8 {
+    let terminal = TERMINAL.get().filter(|_| is_job_control_enabled());
+
+    if let Some(terminal) = terminal {
+        let _ = tcsetpgrp(&terminal.fd, job.pgid);
+        if let Some(tmodes) = job.tmodes.take() {
+            let _ = tcsetattr(&terminal.fd, SetArg::TCSADRAIN, &tmodes.into());
+        }
+    }
+
+    'processes: for process in &mut job.processes {
+        while process.state == ProcessState::Running {
+            let flags = terminal.map(|_| WaitPidFlag::WUNTRACED);
+
+            match waitpid(process.pid, flags) {
+                Ok(WaitStatus::Exited(_, code)) => process.state = ProcessState::Done(code as u8),
+                Ok(WaitStatus::Signaled(_, sig, _)) => {
+                    if sig == Signal::SIGINT {
+                        INTERRUPTED.store(true, Ordering::Relaxed);
+                    }
+                    process.state = ProcessState::Done(128 + sig as u8)
+                }
+                Ok(WaitStatus::Stopped(..)) => {
+                    process.state = ProcessState::Stopped;
+                    break 'processes;
+                }
+                Ok(_) | Err(Errno::EINTR) => {}
+                Err(e) => {
+                    warn!("waitpid {}: {}", process.pid, e);
+                    process.state = ProcessState::Done(1);
+                }
+            }
+        }
+    }
+
+    if let Some(terminal) = terminal {
+        let _ = tcsetpgrp(&terminal.fd, terminal.shell_pgid);
+
+        if job.state() == JobState::Stopped {
+            job.tmodes = tcgetattr(&terminal.fd).ok().map(Into::into);
+        }
+
+        let _ = tcsetattr(&terminal.fd, SetArg::TCSADRAIN, &terminal.tmodes.into());
+    }
+
+    let code = job.status_code();
+
+    if job.state() == JobState::Stopped {
+        // The rest of the group was stopped by the same signal
+        for process in &mut job.processes {
+            if process.state == ProcessState::Running {
+                process.state = ProcessState::Stopped;
+            }
+        }
+
+        if let Ok(mut table) = write_job_table() {
+            let id = table.insert(job);
+            if let Some(job) = table.get(id) {
+                let _ = writeln!(stderr(), "\n{}", table.describe(job, false));
+            }
+        }
+    }
+
+    code
+}
+
+/// Register a job that keeps running while the shell reads the next command
+pub fn add_background(pgid: Pid, command: String, pids: &[Pid]) -> anyhow::Result<usize> {
+    let job = Job::new(pgid, command, pids);
+    let last_pid = job.last_pid().unwrap_or(pgid);
+
+    let id = write_job_table()?.insert(job);
+    let _ = writeln!(stderr(), "[{id}] {last_pid}");
+
+    Ok(id)
+}
+
+/// Find the job named by a job spec, the current job when there is none
+pub fn resolve_job(spec: Option<&str>) -> anyhow::Result<usize> {
+    let table = read_job_table()?;
+
+    match spec {
+        Some(spec) => table
+            .resolve(spec)
+            .ok_or_else(|| anyhow::anyhow!("{spec}: no such job")),
+        None => table
+            .current()
+            .ok_or_else(|| anyhow::anyhow!("current: no such job")),
+    }
+}
+
+/// Resume a job with SIGCONT, in the foreground or the background
+pub fn continue_job(id: usize, foreground: bool) -> anyhow::Result<u8> {
+    anyhow::ensure!(is_job_control_enabled(), "no job control");
+
+    if foreground {
+        let mut job = write_job_table()?
+            .remove(id)
+            .ok_or_else(|| anyhow::anyhow!("%{id}: no such job"))?;
+
+        println!("{}", job.command);
+
+        for process in &mut job.processes {
+            if process.state == ProcessState::Stopped {
+                process.state = ProcessState::Running;
+            }
+        }
+
+        killpg(job.pgid, Signal::SIGCONT)?;
+
+        Ok(wait_foreground(job))
+    } else {
+        let mut table = write_job_table()?;
+        let job = table
+            .get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("%{id}: no such job"))?;
+
+        for process in &mut job.processes {
+            if process.state == ProcessState::Stopped {
+                process.state = ProcessState::Running;
+            }
+        }
+
+        killpg(job.pgid, Signal::SIGCONT)?;
+
+        let command = job.command.clone();
+        table.touch(id);
+        println!("[{id}]{} {command} &", table.marker(id));
+
+        Ok(0)
+    }
+}
+
+/// Collect state changes of background jobs without blocking
+pub fn update_jobs() {
+    let flags = WaitPidFlag::WNOHANG | WaitPidFlag::WUNTRACED | WaitPidFlag::WCONTINUED;
+
+    loop {
+        let (pid, state) = match waitpid(None, Some(flags)) {
+            Ok(WaitStatus::Exited(pid, code)) => (pid, ProcessState::Done(code as u8)),
+            Ok(WaitStatus::Signaled(pid, sig, _)) => (pid, ProcessState::Done(128 + sig as u8)),
+            Ok(WaitStatus::Stopped(pid, _)) => (pid, ProcessState::Stopped),
+            Ok(WaitStatus::Continued(pid)) => (pid, ProcessState::Running),
+            Err(Errno::EINTR) => continue,
+            _ => break,
+        };
+
+        debug!("Job process {} changed to {:?}", pid, state);
+
+        if let Ok(mut table) = write_job_table() {
+            table.update_process(pid, state);
+        }
+    }
+}
+
+/// Print jobs that finished or stopped since the last report, and forget the finished ones
+pub fn report_job_changes() {
+    update_jobs();
+
+    let Ok(mut table) = write_job_table() else {
+        return;
+    };
+
+    for id in table.ids() {
+        let Some(job) = table.get(id) else {
+            continue;
+        };
+
+        if job.notified || job.state() == JobState::Running {
+            continue;
+        }
+
+        let _ = writeln!(stderr(), "{}", table.describe(job, false));
+
+        if matches!(job.state(), JobState::Done(_)) {
+            table.remove(id);
+        } else if let Some(job) = table.get_mut(id) {
+            job.notified = true;
+        }
+    }
+}
+
+pub fn init_module() -> anyhow::Result<()> {
+    let stdin = stdin();
+
+    if !isatty(&stdin).unwrap_or(false) {
+        return Ok(());
+    }
+
+    // Wait until the shell runs in the foreground before taking over the terminal
+    loop {
+        let pgrp = getpgrp();
+        match tcgetpgrp(&stdin) {
+            Ok(foreground) if foreground == pgrp => break,
+            Ok(_) => killpg(pgrp, Signal::SIGTTIN)?,
+            Err(e) => {
+                warn!("Job control disabled: {}", e);
+                return Ok(());
+            }
+        }
+    }
+
+    for sig in JOB_CONTROL_SIGNALS {
+        // SAFETY: SigIgn installs no handler code
+        unsafe { signal(sig, SigHandler::SigIgn) }?;
+    }
+
+    // Fails for session leaders, which already lead their own group
+    let pid = getpid();
+    let _ = setpgid(pid, pid);
+    let shell_pgid = getpgrp();
+
+    let raw = fcntl(&stdin, FcntlArg::F_DUPFD_CLOEXEC(TERMINAL_FD))?;
+    // SAFETY: F_DUPFD_CLOEXEC returned a new descriptor that nothing else owns
+    let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+    tcsetpgrp(&fd, shell_pgid)?;
+    let tmodes = tcgetattr(&fd)?.into();
+
+    let _ = TERMINAL.set(Terminal {
+        fd,
+        shell_pgid,
+        tmodes,
+    });
+    JOB_CONTROL.store(true, Ordering::Relaxed);
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use std::{
+    fmt,
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use nix::{libc, unistd::Pid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessState {
+    Running,
+    Stopped,
+    Done(u8),
+}
+
+#[derive(Debug, Clone)]
+pub struct Process {
+    pub pid: Pid,
+    pub state: ProcessState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Stopped,
+    Done(u8),
+}
+
+impl fmt::Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobState::Running => write!(f, "Running"),
+            JobState::Stopped => write!(f, "Stopped"),
+            JobState::Done(0) => write!(f, "Done"),
+            JobState::Done(code) => write!(f, "Exit {code}"),
+        }
+    }
+}
+
+pub struct Job {
+    /// Number shown as `[n]`, zero until the job enters the table
+    pub id: usize,
+    pub pgid: Pid,
+    pub command: String,
+    pub processes: Vec<Process>,
+    /// Terminal modes saved when the job was stopped, given back when it resumes
+    pub tmodes: Option<libc::termios>,
+    /// Whether the latest state change was reported to the user
+    pub notified: bool,
+}
+
+impl Job {
+    pub fn new(pgid: Pid, command: String, pids: &[Pid]) -> Self {
+        Self {
+            id: 0,
+            pgid,
+            command,
+            processes: pids
+                .iter()
+                .map(|&pid| Process {
+                    pid,
+                    state: ProcessState::Running,
+                })
+                .collect(),
+            tmodes: None,
+            notified: true,
+        }
+    }
+
+    pub fn state(&self) -> JobState {
+        if self
+            .processes
+            .iter()
+            .any(|process| process.state == ProcessState::Stopped)
+        {
+            JobState::Stopped
+        } else if self
+            .processes
+            .iter()
+            .any(|process| process.state == ProcessState::Running)
+        {
+            JobState::Running
+        } else {
+            JobState::Done(self.status_code())
+        }
+    }
+
+    /// Status of the last process, as for a pipeline
+    pub fn status_code(&self) -> u8 {
+        match self.processes.last().map(|process| process.state) {
+            Some(ProcessState::Done(code)) => code,
+            Some(ProcessState::Stopped) => 128 + libc::SIGTSTP as u8,
+            _ => 0,
+        }
+    }
+
+    pub fn last_pid(&self) -> Option<Pid> {
+        self.processes.last().map(|process| process.pid)
+    }
+}
+
+#[derive(Default)]
+pub struct JobTable {
+    jobs: Vec<Job>,
+    /// Job ids from least to most recently used, `%+` is the last one and `%-` the one before
+    recent: Vec<usize>,
+}
+
+static JOB_TABLE: OnceLock<RwLock<JobTable>> = OnceLock::new();
+
+impl JobTable {
+    /// Put a job in the table, giving it a number unless it already had one
+    pub fn insert(&mut self, mut job: Job) -> usize {
+        if job.id == 0 {
+            job.id = self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        }
+
+        let id = job.id;
+        let pos = self.jobs.partition_point(|other| other.id < id);
+        self.jobs.insert(pos, job);
+        self.touch(id);
+
+        id
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Job> {
+        self.recent.retain(|&recent| recent != id);
+
+        let pos = self.jobs.iter().position(|job| job.id == id)?;
+        Some(self.jobs.remove(pos))
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    pub fn ids(&self) -> Vec<usize> {
+        self.jobs.iter().map(|job| job.id).collect()
+    }
+
+    /// Make a job the current one
+    pub fn touch(&mut self, id: usize) {
+        self.recent.retain(|&recent| recent != id);
+        self.recent.push(id);
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.recent.last().copied()
+    }
+
+    pub fn previous(&self) -> Option<usize> {
+        self.recent.iter().rev().nth(1).copied()
+    }
+
+    /// The `+`/`-` marker printed after a job number
+    pub fn marker(&self, id: usize) -> char {
+        if self.current() == Some(id) {
+            '+'
+        } else if self.previous() == Some(id) {
+            '-'
+        } else {
+            ' '
+        }
+    }
+
+    /// Record a state change reported by waitpid, returns false for unknown processes
+    pub fn update_process(&mut self, pid: Pid, state: ProcessState) -> bool {
+        for job in &mut self.jobs {
+            if let Some(process) = job.processes.iter_mut().find(|process| process.pid == pid) {
+                process.state = state;
+                job.notified = false;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Resolve a job spec such as `%1`, `%%`, `%+`, `%-`, `%name` or `1` to a job id
+    pub fn resolve(&self, spec: &str) -> Option<usize> {
+        let spec = spec.strip_prefix('%').unwrap_or(spec);
+
+        match spec {
+            "" | "%" | "+" => self.current(),
+            "-" => self.previous(),
+            _ => match spec.parse::<usize>() {
+                Ok(id) => self.get(id).map(|job| job.id),
+                Err(_) => self
+                    .jobs
+                    .iter()
+                    .rev()
+                    .find(|job| job.command.starts_with(spec))
+                    .map(|job| job.id),
+            },
+        }
+    }
+
+    /// One line describing a job in the format used by `jobs`, `long` adds the process group
+    pub fn describe(&self, job: &Job, long: bool) -> String {
+        let pgid = if long {
+            format!(" {}", job.pgid)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "[{}]{}{}  {:<24}{}",
+            job.id,
+            self.marker(job.id),
+            pgid,
+            job.state().to_string(),
+            job.command
+        )
+    }
+}
+
+fn job_table() -> &'static RwLock<JobTable> {
+    JOB_TABLE.get_or_init(|| RwLock::new(JobTable::default()))
+}
+
+pub fn read_job_table() -> anyhow::Result<RwLockReadGuard<'static, JobTable>> {
+    job_table()
+        .read()
+        .map_err(|_| anyhow::anyhow!("JOB_TABLE read lock poisoned"))
+}
+
+pub fn write_job_table() -> anyhow::Result<RwLockWriteGuard<'static, JobTable>> {
+    job_table()
+        .write()
+        .map_err(|_| anyhow::anyhow!("JOB_TABLE write lock poisoned"))
+}
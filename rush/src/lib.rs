@@ -1,37 +1,94 @@
-use std::{fs::File, time::Instant};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, stdin},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use abi_stable::std_types::RVec;
+use cli::Mode;
+use history::HistoryEntry;
+use jiff::Timestamp;
 use log::{error, info};
 use rustyline::error::ReadlineError;
 
+mod aliases;
+mod cli;
 mod env;
 mod executor;
+mod expand;
+mod functions;
+mod history;
 mod init;
 mod input;
+mod jobs;
+mod parser;
 mod plugin;
 mod shell_builtins;
+mod vars;
 
-pub fn start_shell() -> anyhow::Result<()> {
+/// Read by interactive shells from every config directory
+const RC_FILE_NAME: &str = "rushrc";
+/// Read by login shells from every config directory
+const PROFILE_FILE_NAME: &str = "profile";
+
+pub fn start_shell() -> anyhow::Result<u8> {
     let start = Instant::now();
 
+    let options = match cli::parse_args(std::env::args()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("rush: {e}");
+            eprintln!("Try 'rush --help' for more information.");
+            return Ok(executor::SYNTAX_ERROR);
+        }
+    };
+
+    match options.mode {
+        Mode::Help => {
+            cli::print_help();
+            return Ok(0);
+        }
+        Mode::Version => {
+            println!("{}", env!("CARGO_PKG_VERSION"));
+            return Ok(0);
+        }
+        _ => {}
+    }
+
+    let interactive = matches!(options.mode, Mode::Interactive);
+
     // Init init module
-    init::init_module()?;
+    init::init_module(interactive)?;
 
     // Init env module and add user paths
     env::init_module()?;
     env::add_rush_data_dirs(init::get_user_data_dir()?, true)?;
     env::add_rush_config_dirs(init::get_user_config_dir()?, true)?;
 
+    // Init shell variables from the environment
+    vars::init_module()?;
+    {
+        let mut vars = vars::write_vars()?;
+        vars.set_shell_name(&options.name);
+        vars.set_positional(options.args);
+    }
+
     shell_builtins::init_module()?;
 
     // Init plugin module
     plugin::init_module()?;
 
-    // Init command executor module
-    executor::init_module()?;
+    if interactive {
+        // Init command executor module
+        executor::init_module()?;
+
+        // Init job control module
+        jobs::init_module()?;
 
-    // Init user input module
-    input::init_module()?;
+        // Init user input module
+        input::init_module()?;
+    }
 
     let elapsed = start.elapsed();
 
@@ -45,30 +102,112 @@ pub fn start_shell() -> anyhow::Result<()> {
 
     info!("Shell initialization took: {}", elapsed_string);
 
-    enter_repl()?;
+    if options.login {
+        run_config_files(PROFILE_FILE_NAME)?;
+    }
+
+    if interactive && !options.norc {
+        match &options.rcfile {
+            Some(path) => run_file(path)?,
+            None => run_config_files(RC_FILE_NAME)?,
+        }
+    }
+
+    match options.mode {
+        Mode::Command(command) => run_commands(command.as_bytes())?,
+        Mode::Script(path) => match File::open(&path) {
+            Ok(file) => run_commands(BufReader::new(file))?,
+            Err(e) => {
+                eprintln!("rush: {}: {}", path.display(), e);
+                return Ok(executor::COMMAND_NOT_FOUND);
+            }
+        },
+        Mode::Stdin => run_commands(stdin().lock())?,
+        _ => {
+            enter_repl()?;
+
+            eprintln!("quit");
+        }
+    }
+
+    Ok(vars::read_vars()?.last_status())
+}
 
-    let history_file = init::get_user_cache_dir()?.join(".history");
-    input::save_history(&history_file)?;
+/// Run the file with this name from every config directory
+///
+/// The directory with the lowest precedence goes first, so that user settings override
+/// those shared from `/etc/rush`.
+fn run_config_files(file_name: &str) -> anyhow::Result<()> {
+    let paths: Vec<PathBuf> = env::read_rush_config_dirs()?
+        .iter()
+        .rev()
+        .map(|dir| dir.join(file_name))
+        .filter(|path| path.is_file())
+        .collect();
 
-    eprintln!("quit");
+    for path in paths {
+        info!("Reading {}", path.display());
+        run_file(&path)?;
+    }
 
     Ok(())
 }
 
+/// Run a startup file, reporting files that cannot be read
+fn run_file(path: &Path) -> anyhow::Result<()> {
+    match File::open(path) {
+        Ok(file) => run_commands(BufReader::new(file)),
+        Err(e) => {
+            eprintln!("rush: {}: {}", path.display(), e);
+            Ok(())
+        }
+    }
+}
+
+/// Run commands read from a script, one complete command at a time
+fn run_commands(mut reader: impl BufRead) -> anyhow::Result<()> {
+    let mut source = String::new();
+
+    loop {
+        let read = reader.read_line(&mut source)?;
+
+        // Constructs spanning several lines run once they are complete
+        if read > 0 && parser::is_incomplete(&source) {
+            continue;
+        }
+
+        if !source.trim().is_empty() {
+            executor::execute_user_input(&source);
+        }
+
+        if read == 0 {
+            return Ok(());
+        }
+
+        source.clear();
+    }
+}
+
 fn enter_repl() -> anyhow::Result<()> {
-    let history_file = init::get_user_cache_dir()?.join(".history");
-    let _ = File::create_new(&history_file);
+    let history_file = init::get_user_data_dir()?.join(history::HISTORY_FILE_NAME);
+    let legacy_file = init::get_user_cache_dir()?.join(history::LEGACY_HISTORY_FILE_NAME);
+    history::write_history()?.load(&history_file)?;
+    history::write_history()?.import_legacy(&legacy_file)?;
 
-    input::load_history(&history_file)?;
+    for entry in history::read_history()?.entries() {
+        input::add_history(&entry.command)?;
+    }
 
     // Enter main loop
     loop {
+        jobs::report_job_changes();
+
         let prompt = executor::execute_command("rush-prompt", RVec::new()).message;
 
         match input::readline(&prompt) {
             Ok(line) => {
                 input::add_history(&line)?;
-                executor::execute_user_input(&line);
+                run_line(&line)?;
             }
             Err(ReadlineError::Interrupted) => {
                 eprintln!("^C");
@@ -85,3 +224,56 @@ fn enter_repl() -> anyhow::Result<()> {
 
     Ok(()) // It's not ok but fine, we gonna handle later
 }
+
+/// Run a line entered at the prompt, recording it in the history with how it ran
+fn run_line(line: &str) -> anyhow::Result<()> {
+    // Recorded before it runs, since a line such as `exit` does not return
+    let added = if line.trim().is_empty() {
+        None
+    } else {
+        let entry = HistoryEntry::new(line, Timestamp::now(), history::current_dir());
+        Some(history::write_history()?.add(entry)?)
+    };
+
+    // The line editor takes SIGINT over while it exists, the shell catches it while lines run
+    let editor_interrupts = jobs::catch_interrupts()?;
+    let start = Instant::now();
+    let status = executor::execute_user_input(line);
+    jobs::restore_interrupts(&editor_interrupts)?;
+
+    if let Some(added) = added {
+        history::write_history()?.finish(added, status.code, start.elapsed())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use nix::{
+        sys::signal::{SigHandler, Signal, kill, signal},
+        unistd::getpid,
+    };
+
+    use super::*;
+
+    #[test]
+    fn interrupt_stops_loop_without_child_process() {
+        // Stands in for the line editor, which takes SIGINT over from the shell
+        // SAFETY: SigIgn installs no handler code
+        unsafe { signal(Signal::SIGINT, SigHandler::SigIgn) }.unwrap();
+
+        let (done, finished) = mpsc::channel();
+        thread::spawn(move || {
+            run_line("i=0; while true; do i=$((i+1)); done").unwrap();
+            done.send(()).unwrap();
+        });
+
+        thread::sleep(Duration::from_millis(200));
+        kill(getpid(), Signal::SIGINT).unwrap();
+
+        assert!(finished.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
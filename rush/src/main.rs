@@ -1,7 +1,13 @@
+use std::process;
+
 use rush::start_shell;
 
 fn main() {
-    if let Err(e) = start_shell() {
-        eprintln!("{e}");
+    match start_shell() {
+        Ok(code) => process::exit(code.into()),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
     }
 }
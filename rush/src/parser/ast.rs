@@ -0,0 +1,451 @@
+use std::fmt;
+
+/// A piece of a word, remembering how it was quoted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    /// Unquoted text
+    Literal(String),
+    /// Text protected by single quotes or a backslash
+    Quoted(String),
+    /// Parts found between double quotes
+    DoubleQuoted(Vec<WordPart>),
+    /// `$name` or `${...}`
+    Parameter(Parameter),
+    /// `$(list)` or `` `list` ``, replaced by the output of the commands
+    CommandSubst(List),
+    /// `$((expression))`
+    Arithmetic(Word),
+}
+
+/// A parameter expansion such as `$HOME`, `$?` or `${VAR:-default}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub op: ParameterOp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterOp {
+    /// `$VAR` or `${VAR}`
+    Value,
+    /// `${#VAR}`
+    Length,
+    /// `${VAR:-word}`
+    Default(Word),
+    /// `${VAR:=word}`
+    Assign(Word),
+    /// `${VAR#pattern}`
+    RemovePrefix(Word),
+    /// `${VAR##pattern}`
+    RemoveLongestPrefix(Word),
+    /// `${VAR%pattern}`
+    RemoveSuffix(Word),
+    /// `${VAR%%pattern}`
+    RemoveLongestSuffix(Word),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+}
+
+impl Word {
+    /// Text of the word once quotes are removed
+    pub fn to_unquoted(&self) -> String {
+        fn push_parts(parts: &[WordPart], out: &mut String) {
+            for part in parts {
+                match part {
+                    WordPart::Literal(text) | WordPart::Quoted(text) => out.push_str(text),
+                    WordPart::DoubleQuoted(inner) => push_parts(inner, out),
+                    WordPart::Parameter(param) => out.push_str(&param.to_string()),
+                    WordPart::CommandSubst(list) => out.push_str(&format!("$({list})")),
+                    WordPart::Arithmetic(expr) => out.push_str(&format!("$(({expr}))")),
+                }
+            }
+        }
+
+        let mut out = String::new();
+        push_parts(&self.parts, &mut out);
+        out
+    }
+
+    /// Text of the word if it contains no quoting at all
+    pub fn as_literal(&self) -> Option<&str> {
+        match self.parts.as_slice() {
+            [WordPart::Literal(text)] => Some(text),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectKind {
+    /// `<`
+    Input,
+    /// `>`
+    Output,
+    /// `>>`
+    Append,
+    /// `<&`
+    DupInput,
+    /// `>&`
+    DupOutput,
+    /// `&>`
+    OutputAll,
+    /// `&>>`
+    AppendAll,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Explicit file descriptor written before the operator, as in `2>`
+    pub fd: Option<u32>,
+    pub kind: RedirectKind,
+    pub target: Word,
+}
+
+/// `NAME=value`, written before the command name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub name: String,
+    pub value: Word,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimpleCommand {
+    pub assignments: Vec<Assignment>,
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompoundCommand {
+    /// `{ list; }`
+    Group(List),
+    /// `if list; then list; [elif list; then list;]... [else list;] fi`
+    If {
+        branches: Vec<(List, List)>,
+        else_body: Option<List>,
+    },
+    /// `while list; do list; done`
+    While { condition: List, body: List },
+    /// `until list; do list; done`
+    Until { condition: List, body: List },
+    /// `for name [in word...]; do list; done`, without `in` it loops over `"$@"`
+    For {
+        name: String,
+        words: Option<Vec<Word>>,
+        body: List,
+    },
+    /// `case word in [pattern [| pattern]...) list;;]... esac`
+    Case { word: Word, arms: Vec<CaseArm> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseArm {
+    pub patterns: Vec<Word>,
+    pub body: List,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Simple(SimpleCommand),
+    Compound(CompoundCommand, Vec<Redirect>),
+    /// `name() compound-command`, its body is a compound command
+    FunctionDef(String, Box<Command>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AndOrOp {
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndOr {
+    pub first: Pipeline,
+    pub rest: Vec<(AndOrOp, Pipeline)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// `;`, a newline or the end of input
+    Sequential,
+    /// `&`
+    Background,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    pub and_or: AndOr,
+    pub separator: Separator,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct List {
+    pub items: Vec<ListItem>,
+}
+
+fn write_parts(
+    f: &mut fmt::Formatter<'_>,
+    parts: &[WordPart],
+    in_double_quotes: bool,
+) -> fmt::Result {
+    for part in parts {
+        match part {
+            WordPart::Literal(text) if in_double_quotes => {
+                for c in text.chars() {
+                    if matches!(c, '"' | '\\' | '$' | '`') {
+                        write!(f, "\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+            }
+            WordPart::Literal(text) => write!(f, "{text}")?,
+            WordPart::Quoted(text) if in_double_quotes => {
+                write_parts(f, &[WordPart::Literal(text.clone())], true)?
+            }
+            WordPart::Quoted(text) => write!(f, "'{}'", text.replace('\'', r"'\''"))?,
+            WordPart::DoubleQuoted(inner) => {
+                write!(f, "\"")?;
+                write_parts(f, inner, true)?;
+                write!(f, "\"")?;
+            }
+            WordPart::Parameter(param) => write!(f, "{param}")?,
+            WordPart::CommandSubst(list) => write!(f, "$({list})")?,
+            WordPart::Arithmetic(expr) => write!(f, "$(({expr}))")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Words are printed with their original quoting, so the text parses back to the same word
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_parts(f, &self.parts, false)
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+
+        match &self.op {
+            ParameterOp::Value => write!(f, "${{{name}}}"),
+            ParameterOp::Length => write!(f, "${{#{name}}}"),
+            ParameterOp::Default(word) => write!(f, "${{{name}:-{word}}}"),
+            ParameterOp::Assign(word) => write!(f, "${{{name}:={word}}}"),
+            ParameterOp::RemovePrefix(word) => write!(f, "${{{name}#{word}}}"),
+            ParameterOp::RemoveLongestPrefix(word) => write!(f, "${{{name}##{word}}}"),
+            ParameterOp::RemoveSuffix(word) => write!(f, "${{{name}%{word}}}"),
+            ParameterOp::RemoveLongestSuffix(word) => write!(f, "${{{name}%%{word}}}"),
+        }
+    }
+}
+
+impl fmt::Display for Redirect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(fd) = self.fd {
+            write!(f, "{fd}")?;
+        }
+
+        let op = match self.kind {
+            RedirectKind::Input => "<",
+            RedirectKind::Output => ">",
+            RedirectKind::Append => ">>",
+            RedirectKind::DupInput => "<&",
+            RedirectKind::DupOutput => ">&",
+            RedirectKind::OutputAll => "&>",
+            RedirectKind::AppendAll => "&>>",
+        };
+
+        write!(f, "{op}{}", self.target)
+    }
+}
+
+/// Write items separated by a single space
+fn write_spaced<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{item}")?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+impl fmt::Display for SimpleCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_spaced(f, &self.assignments)?;
+
+        if !self.assignments.is_empty() && !self.words.is_empty() {
+            write!(f, " ")?;
+        }
+
+        write_spaced(f, &self.words)?;
+
+        let has_words = !self.assignments.is_empty() || !self.words.is_empty();
+        if has_words && !self.redirects.is_empty() {
+            write!(f, " ")?;
+        }
+
+        write_spaced(f, &self.redirects)
+    }
+}
+
+/// A list followed by whatever separator it needs before a closing reserved word
+struct Terminated<'a>(&'a List);
+
+impl fmt::Display for Terminated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.items.last() {
+            Some(item) if item.separator == Separator::Background => write!(f, "{} ", self.0),
+            Some(_) => write!(f, "{}; ", self.0),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for CompoundCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompoundCommand::Group(body) => write!(f, "{{ {}}}", Terminated(body)),
+            CompoundCommand::If {
+                branches,
+                else_body,
+            } => {
+                for (index, (condition, body)) in branches.iter().enumerate() {
+                    let keyword = if index == 0 { "if" } else { "elif" };
+                    write!(
+                        f,
+                        "{keyword} {}then {}",
+                        Terminated(condition),
+                        Terminated(body)
+                    )?;
+                }
+                if let Some(body) = else_body {
+                    write!(f, "else {}", Terminated(body))?;
+                }
+                write!(f, "fi")
+            }
+            CompoundCommand::While { condition, body } => {
+                write!(
+                    f,
+                    "while {}do {}done",
+                    Terminated(condition),
+                    Terminated(body)
+                )
+            }
+            CompoundCommand::Until { condition, body } => {
+                write!(
+                    f,
+                    "until {}do {}done",
+                    Terminated(condition),
+                    Terminated(body)
+                )
+            }
+            CompoundCommand::For { name, words, body } => {
+                write!(f, "for {name}")?;
+                if let Some(words) = words {
+                    write!(f, " in")?;
+                    for word in words {
+                        write!(f, " {word}")?;
+                    }
+                }
+                write!(f, "; do {}done", Terminated(body))
+            }
+            CompoundCommand::Case { word, arms } => {
+                write!(f, "case {word} in ")?;
+                for arm in arms {
+                    for (index, pattern) in arm.patterns.iter().enumerate() {
+                        if index > 0 {
+                            write!(f, " | ")?;
+                        }
+                        write!(f, "{pattern}")?;
+                    }
+                    write!(f, ") {};; ", arm.body)?;
+                }
+                write!(f, "esac")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Simple(simple) => write!(f, "{simple}"),
+            Command::Compound(compound, redirects) => {
+                write!(f, "{compound}")?;
+                for redirect in redirects {
+                    write!(f, " {redirect}")?;
+                }
+                Ok(())
+            }
+            Command::FunctionDef(name, body) => write!(f, "{name}() {body}"),
+        }
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, command) in self.commands.iter().enumerate() {
+            if index > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{command}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for AndOr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.first)?;
+
+        for (op, pipeline) in &self.rest {
+            let op = match op {
+                AndOrOp::And => "&&",
+                AndOrOp::Or => "||",
+            };
+            write!(f, " {op} {pipeline}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, item) in self.items.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", item.and_or)?;
+
+            match item.separator {
+                Separator::Background => write!(f, " &")?,
+                Separator::Sequential if index + 1 < self.items.len() => write!(f, ";")?,
+                Separator::Sequential => {}
+            }
+        }
+
+        Ok(())
+    }
+}
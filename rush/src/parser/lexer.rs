@@ -0,0 +1,836 @@
+use std::fmt;
+
+use super::{
+    ParseError,
+    ast::{Parameter, ParameterOp, Word, WordPart},
+    parse,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Pipe,
+    AndIf,
+    OrIf,
+    Semi,
+    DSemi,
+    Amp,
+    LParen,
+    RParen,
+    Less,
+    Great,
+    DGreat,
+    LessAnd,
+    GreatAnd,
+    AndGreat,
+    AndDGreat,
+}
+
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Pipe => "|",
+            Operator::AndIf => "&&",
+            Operator::OrIf => "||",
+            Operator::Semi => ";",
+            Operator::DSemi => ";;",
+            Operator::Amp => "&",
+            Operator::LParen => "(",
+            Operator::RParen => ")",
+            Operator::Less => "<",
+            Operator::Great => ">",
+            Operator::DGreat => ">>",
+            Operator::LessAnd => "<&",
+            Operator::GreatAnd => ">&",
+            Operator::AndGreat => "&>",
+            Operator::AndDGreat => "&>>",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Word(Word),
+    Operator(Operator),
+    /// Digits directly followed by a redirection operator, as the `2` in `2>`
+    IoNumber(u32),
+    Newline,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word.to_unquoted()),
+            Token::Operator(op) => write!(f, "{}", op.as_str()),
+            Token::IoNumber(fd) => write!(f, "{fd}"),
+            Token::Newline => write!(f, "newline"),
+        }
+    }
+}
+
+fn is_operator_start(c: char) -> bool {
+    matches!(c, '|' | '&' | ';' | '<' | '>' | '(' | ')')
+}
+
+fn is_blank(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Parameters named by a single punctuation character, such as `$?`
+fn is_special_parameter(c: char) -> bool {
+    matches!(c, '?' | '$' | '#' | '@' | '*')
+}
+
+/// Split a command line into tokens, keeping the quoting of every word
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    Lexer::new(input).tokenize()
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    /// Skip any number of backslash-newline pairs
+    fn skip_line_continuations(&mut self) {
+        while self.peek() == Some('\\') && self.peek_at(1) == Some('\n') {
+            self.pos += 2;
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_line_continuations();
+
+            let Some(c) = self.peek() else {
+                break;
+            };
+
+            if is_blank(c) {
+                self.pos += 1;
+            } else if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c == '\n' {
+                self.pos += 1;
+                tokens.push(Token::Newline);
+            } else if is_operator_start(c) {
+                tokens.push(Token::Operator(self.read_operator()));
+            } else {
+                let word = self.read_word()?;
+
+                let is_io_number = matches!(self.peek(), Some('<' | '>'))
+                    && word
+                        .as_literal()
+                        .is_some_and(|text| text.chars().all(|c| c.is_ascii_digit()));
+
+                match word.as_literal().map(str::parse::<u32>) {
+                    Some(Ok(fd)) if is_io_number => tokens.push(Token::IoNumber(fd)),
+                    _ => tokens.push(Token::Word(word)),
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    fn read_operator(&mut self) -> Operator {
+        let c = self.bump().unwrap_or_default();
+        self.skip_line_continuations();
+        let next = self.peek();
+
+        let (op, extra) = match (c, next) {
+            ('|', Some('|')) => (Operator::OrIf, 1),
+            ('|', _) => (Operator::Pipe, 0),
+            ('&', Some('&')) => (Operator::AndIf, 1),
+            ('&', Some('>')) => {
+                self.pos += 1;
+                self.skip_line_continuations();
+                if self.peek() == Some('>') {
+                    (Operator::AndDGreat, 1)
+                } else {
+                    (Operator::AndGreat, 0)
+                }
+            }
+            ('&', _) => (Operator::Amp, 0),
+            (';', Some(';')) => (Operator::DSemi, 1),
+            (';', _) => (Operator::Semi, 0),
+            ('(', _) => (Operator::LParen, 0),
+            (')', _) => (Operator::RParen, 0),
+            ('<', Some('&')) => (Operator::LessAnd, 1),
+            ('<', _) => (Operator::Less, 0),
+            ('>', Some('>')) => (Operator::DGreat, 1),
+            ('>', Some('&')) => (Operator::GreatAnd, 1),
+            _ => (Operator::Great, 0),
+        };
+
+        self.pos += extra;
+        op
+    }
+
+    fn read_word(&mut self) -> Result<Word, ParseError> {
+        let parts = self.read_word_parts(|c| is_blank(c) || c == '\n' || is_operator_start(c))?;
+        Ok(Word { parts })
+    }
+
+    /// Read word parts up to the first unquoted character accepted by `is_end`
+    fn read_word_parts(&mut self, is_end: fn(char) -> bool) -> Result<Vec<WordPart>, ParseError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+
+        while let Some(c) = self.peek() {
+            if is_end(c) {
+                break;
+            }
+
+            match c {
+                '\'' => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::Quoted(self.read_single_quoted()?));
+                }
+                '"' => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::DoubleQuoted(self.read_double_quoted()?));
+                }
+                '\\' => match self.peek_at(1) {
+                    Some('\n') => self.pos += 2,
+                    Some(escaped) => {
+                        flush_literal(&mut literal, &mut parts);
+                        self.pos += 2;
+                        push_quoted(escaped, &mut parts);
+                    }
+                    None => return Err(ParseError::Incomplete("trailing backslash")),
+                },
+                '$' => match self.read_dollar()? {
+                    Some(part) => {
+                        flush_literal(&mut literal, &mut parts);
+                        parts.push(part);
+                    }
+                    None => literal.push(c),
+                },
+                '`' => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(self.read_backquoted()?);
+                }
+                _ => {
+                    self.pos += 1;
+                    literal.push(c);
+                }
+            }
+        }
+
+        flush_literal(&mut literal, &mut parts);
+
+        Ok(parts)
+    }
+
+    /// Read an expansion starting at `$`, or consume a lone `$` and return `None`
+    fn read_dollar(&mut self) -> Result<Option<WordPart>, ParseError> {
+        self.pos += 1;
+
+        let name = match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                return self.read_braced_parameter().map(Some);
+            }
+            Some('(') if self.peek_at(1) == Some('(') => {
+                self.pos += 2;
+                return self.read_arithmetic().map(Some);
+            }
+            Some('(') => {
+                self.pos += 1;
+                let text = self.read_until_close_paren()?;
+                return parse(&text).map(|list| Some(WordPart::CommandSubst(list)));
+            }
+            Some(c) if is_name_start(c) => self.read_name(),
+            Some(c) if c.is_ascii_digit() || is_special_parameter(c) => {
+                self.pos += 1;
+                c.to_string()
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(WordPart::Parameter(Parameter {
+            name,
+            op: ParameterOp::Value,
+        })))
+    }
+
+    fn read_name(&mut self) -> String {
+        let mut name = String::new();
+
+        while let Some(c) = self.peek().filter(|&c| is_name_char(c)) {
+            self.pos += 1;
+            name.push(c);
+        }
+
+        name
+    }
+
+    /// Read the inside of `${...}`, after the opening brace
+    fn read_braced_parameter(&mut self) -> Result<WordPart, ParseError> {
+        let start = self.pos;
+
+        // `${#}` is the number of arguments, `${#VAR}` the length of VAR
+        let length = self.peek() == Some('#') && self.peek_at(1).is_some_and(|c| c != '}');
+        if length {
+            self.pos += 1;
+        }
+
+        let name = match self.peek() {
+            Some(c) if is_name_start(c) => self.read_name(),
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+                    self.pos += 1;
+                    digits.push(c);
+                }
+                digits
+            }
+            Some(c) if is_special_parameter(c) => {
+                self.pos += 1;
+                c.to_string()
+            }
+            _ => String::new(),
+        };
+
+        let op = match (self.peek(), self.peek_at(1)) {
+            _ if name.is_empty() => None,
+            (Some('}'), _) if length => Some(ParameterOp::Length),
+            (Some('}'), _) => Some(ParameterOp::Value),
+            _ if length => None,
+            (Some(':'), Some('-')) => {
+                self.pos += 2;
+                Some(ParameterOp::Default(self.read_operand()?))
+            }
+            (Some(':'), Some('=')) => {
+                self.pos += 2;
+                Some(ParameterOp::Assign(self.read_operand()?))
+            }
+            (Some('#'), Some('#')) => {
+                self.pos += 2;
+                Some(ParameterOp::RemoveLongestPrefix(self.read_operand()?))
+            }
+            (Some('#'), _) => {
+                self.pos += 1;
+                Some(ParameterOp::RemovePrefix(self.read_operand()?))
+            }
+            (Some('%'), Some('%')) => {
+                self.pos += 2;
+                Some(ParameterOp::RemoveLongestSuffix(self.read_operand()?))
+            }
+            (Some('%'), _) => {
+                self.pos += 1;
+                Some(ParameterOp::RemoveSuffix(self.read_operand()?))
+            }
+            _ => None,
+        };
+
+        match op {
+            Some(op) if self.peek() == Some('}') => {
+                self.pos += 1;
+                Ok(WordPart::Parameter(Parameter { name, op }))
+            }
+            _ => {
+                // Skip to the closing brace so the error shows the whole expansion
+                while self.peek().is_some_and(|c| c != '}') {
+                    self.pos += 1;
+                }
+                if self.bump().is_none() {
+                    return Err(ParseError::Incomplete("missing `}'"));
+                }
+
+                let text: String = self.chars[start..self.pos - 1].iter().collect();
+                Err(ParseError::BadSubstitution(format!("${{{text}}}")))
+            }
+        }
+    }
+
+    /// Read the word following an operator inside `${...}`, up to the closing brace
+    fn read_operand(&mut self) -> Result<Word, ParseError> {
+        let parts = self.read_word_parts(|c| c == '}')?;
+
+        if self.peek().is_none() {
+            return Err(ParseError::Incomplete("missing `}'"));
+        }
+
+        Ok(Word { parts })
+    }
+
+    /// Collect the text of `$(...)` up to its matching parenthesis, skipping quoted parentheses
+    fn read_until_close_paren(&mut self) -> Result<String, ParseError> {
+        let mut text = String::new();
+        let mut depth = 1;
+
+        loop {
+            let Some(c) = self.bump() else {
+                return Err(ParseError::Incomplete("missing `)'"));
+            };
+
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(text);
+                    }
+                }
+                '\\' => {
+                    text.push(c);
+                    match self.bump() {
+                        Some(escaped) => text.push(escaped),
+                        None => return Err(ParseError::Incomplete("missing `)'")),
+                    }
+                    continue;
+                }
+                '\'' | '"' => {
+                    text.push(c);
+                    loop {
+                        match self.bump() {
+                            Some('\\') if c == '"' => {
+                                text.push('\\');
+                                if let Some(escaped) = self.bump() {
+                                    text.push(escaped);
+                                }
+                            }
+                            Some(quote) if quote == c => break,
+                            Some(other) => text.push(other),
+                            None => return Err(ParseError::Incomplete("missing `)'")),
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            text.push(c);
+        }
+    }
+
+    /// Read `$((expression))` after its opening parentheses, expansions inside are kept as parts
+    fn read_arithmetic(&mut self) -> Result<WordPart, ParseError> {
+        let text = self.read_until_close_paren()?;
+
+        match self.bump() {
+            Some(')') => {}
+            Some(_) => return Err(ParseError::BadSubstitution(format!("$(({text})"))),
+            None => return Err(ParseError::Incomplete("missing `))'")),
+        }
+
+        let mut lexer = Lexer::new(&text);
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+
+        // Like between double quotes, only `$`, backquotes and backslashes are special
+        while let Some(c) = lexer.peek() {
+            match c {
+                '$' => match lexer.read_dollar()? {
+                    Some(part) => {
+                        flush_literal(&mut literal, &mut parts);
+                        parts.push(part);
+                    }
+                    None => literal.push(c),
+                },
+                '`' => {
+                    flush_literal(&mut literal, &mut parts);
+                    lexer.pos += 1;
+                    parts.push(lexer.read_backquoted()?);
+                }
+                '\\' => {
+                    lexer.pos += 1;
+                    literal.extend(lexer.bump());
+                }
+                _ => {
+                    lexer.pos += 1;
+                    literal.push(c);
+                }
+            }
+        }
+
+        flush_literal(&mut literal, &mut parts);
+
+        Ok(WordPart::Arithmetic(Word { parts }))
+    }
+
+    /// Read a `` `command` `` substitution after its opening backquote
+    fn read_backquoted(&mut self) -> Result<WordPart, ParseError> {
+        let mut text = String::new();
+
+        loop {
+            match self.bump() {
+                Some('`') => break,
+                Some('\\') => match self.bump() {
+                    // A backslash only escapes these characters between backquotes
+                    Some(c @ ('$' | '`' | '\\')) => text.push(c),
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => return Err(ParseError::Incomplete("unterminated backquote")),
+                },
+                Some(c) => text.push(c),
+                None => return Err(ParseError::Incomplete("unterminated backquote")),
+            }
+        }
+
+        parse(&text).map(WordPart::CommandSubst)
+    }
+
+    fn read_single_quoted(&mut self) -> Result<String, ParseError> {
+        let mut text = String::new();
+
+        loop {
+            match self.bump() {
+                Some('\'') => return Ok(text),
+                Some(c) => text.push(c),
+                None => return Err(ParseError::Incomplete("unterminated single quote")),
+            }
+        }
+    }
+
+    fn read_double_quoted(&mut self) -> Result<Vec<WordPart>, ParseError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.bump() {
+                        Some('\n') => {}
+                        // Only these characters are special after a backslash inside double quotes
+                        Some(c @ ('$' | '`' | '"' | '\\')) => text.push(c),
+                        Some(c) => {
+                            text.push('\\');
+                            text.push(c);
+                        }
+                        None => return Err(ParseError::Incomplete("unterminated double quote")),
+                    }
+                }
+                Some('$') => match self.read_dollar()? {
+                    Some(part) => {
+                        flush_literal(&mut text, &mut parts);
+                        parts.push(part);
+                    }
+                    None => text.push('$'),
+                },
+                Some('`') => {
+                    flush_literal(&mut text, &mut parts);
+                    self.pos += 1;
+                    parts.push(self.read_backquoted()?);
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    text.push(c);
+                }
+                None => return Err(ParseError::Incomplete("unterminated double quote")),
+            }
+        }
+
+        flush_literal(&mut text, &mut parts);
+
+        Ok(parts)
+    }
+}
+
+fn flush_literal(literal: &mut String, parts: &mut Vec<WordPart>) {
+    if !literal.is_empty() {
+        parts.push(WordPart::Literal(std::mem::take(literal)));
+    }
+}
+
+/// Append a backslash-escaped character, merging with a preceding escape
+fn push_quoted(c: char, parts: &mut Vec<WordPart>) {
+    if let Some(WordPart::Quoted(text)) = parts.last_mut() {
+        text.push(c);
+    } else {
+        parts.push(WordPart::Quoted(c.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(text: &str) -> WordPart {
+        WordPart::Literal(text.to_string())
+    }
+
+    fn quoted(text: &str) -> WordPart {
+        WordPart::Quoted(text.to_string())
+    }
+
+    fn word(parts: Vec<WordPart>) -> Token {
+        Token::Word(Word { parts })
+    }
+
+    fn parameter(name: &str) -> WordPart {
+        WordPart::Parameter(Parameter {
+            name: name.to_string(),
+            op: ParameterOp::Value,
+        })
+    }
+
+    #[test]
+    fn splits_words_on_blanks() {
+        assert_eq!(
+            tokenize("echo  a\tb\n").unwrap(),
+            vec![
+                word(vec![literal("echo")]),
+                word(vec![literal("a")]),
+                word(vec![literal("b")]),
+                Token::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_quoting() {
+        assert_eq!(
+            tokenize(r#"'a b'"c $X"d"#).unwrap(),
+            vec![word(vec![
+                quoted("a b"),
+                WordPart::DoubleQuoted(vec![literal("c "), parameter("X")]),
+                literal("d"),
+            ])]
+        );
+        assert_eq!(
+            tokenize("'$X \\'").unwrap(),
+            vec![word(vec![quoted("$X \\")])]
+        );
+    }
+
+    #[test]
+    fn reads_backslash_escapes() {
+        assert_eq!(
+            tokenize(r"a\ b\\").unwrap(),
+            vec![word(vec![
+                literal("a"),
+                quoted(" "),
+                literal("b"),
+                quoted("\\")
+            ])]
+        );
+        // Consecutive escapes make a single quoted part
+        assert_eq!(tokenize(r"\a\b").unwrap(), vec![word(vec![quoted("ab")])]);
+        assert_eq!(tokenize(r"\|").unwrap(), vec![word(vec![quoted("|")])]);
+        // Between double quotes only some characters lose their backslash
+        assert_eq!(
+            tokenize(r#""\$\"\q""#).unwrap(),
+            vec![word(vec![WordPart::DoubleQuoted(vec![literal("$\"\\q")])])]
+        );
+    }
+
+    #[test]
+    fn joins_line_continuations() {
+        assert_eq!(
+            tokenize("ec\\\nho \\\n a").unwrap(),
+            vec![word(vec![literal("echo")]), word(vec![literal("a")])]
+        );
+        assert_eq!(
+            tokenize("\"a\\\nb\"").unwrap(),
+            vec![word(vec![WordPart::DoubleQuoted(vec![literal("ab")])])]
+        );
+        assert_eq!(
+            tokenize("&\\\n&").unwrap(),
+            vec![Token::Operator(Operator::AndIf)]
+        );
+    }
+
+    #[test]
+    fn skips_comments() {
+        assert_eq!(
+            tokenize("a # b 'c\nd").unwrap(),
+            vec![
+                word(vec![literal("a")]),
+                Token::Newline,
+                word(vec![literal("d")]),
+            ]
+        );
+        assert_eq!(tokenize("a#b").unwrap(), vec![word(vec![literal("a#b")])]);
+    }
+
+    #[test]
+    fn reads_longest_operator() {
+        let ops: Vec<Token> = [
+            Operator::OrIf,
+            Operator::Pipe,
+            Operator::DSemi,
+            Operator::AndDGreat,
+            Operator::AndGreat,
+            Operator::GreatAnd,
+            Operator::DGreat,
+            Operator::LessAnd,
+        ]
+        .into_iter()
+        .map(Token::Operator)
+        .collect();
+
+        assert_eq!(tokenize("|| | ;; &>> &> >& >> <&").unwrap(), ops);
+    }
+
+    #[test]
+    fn reads_io_numbers() {
+        assert_eq!(
+            tokenize("2>&1").unwrap(),
+            vec![
+                Token::IoNumber(2),
+                Token::Operator(Operator::GreatAnd),
+                word(vec![literal("1")]),
+            ]
+        );
+        assert_eq!(
+            tokenize("10<f").unwrap(),
+            vec![
+                Token::IoNumber(10),
+                Token::Operator(Operator::Less),
+                word(vec![literal("f")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn io_numbers_need_digits_right_before_the_operator() {
+        assert_eq!(
+            tokenize("2 >f").unwrap()[..2],
+            [word(vec![literal("2")]), Token::Operator(Operator::Great)]
+        );
+        assert_eq!(tokenize("a2>f").unwrap()[0], word(vec![literal("a2")]));
+        assert_eq!(tokenize("'2'>f").unwrap()[0], word(vec![quoted("2")]));
+    }
+
+    #[test]
+    fn reads_expansions() {
+        assert_eq!(
+            tokenize("$A$1$?${#B}$").unwrap(),
+            vec![word(vec![
+                parameter("A"),
+                parameter("1"),
+                parameter("?"),
+                WordPart::Parameter(Parameter {
+                    name: "B".to_string(),
+                    op: ParameterOp::Length,
+                }),
+                literal("$"),
+            ])]
+        );
+        assert_eq!(
+            tokenize("$((1 + $X))").unwrap(),
+            vec![word(vec![WordPart::Arithmetic(Word {
+                parts: vec![literal("1 + "), parameter("X")],
+            })])]
+        );
+    }
+
+    #[test]
+    fn reads_pattern_removals() {
+        let removal = |op: fn(Word) -> ParameterOp, pattern: &str| {
+            word(vec![WordPart::Parameter(Parameter {
+                name: "F".to_string(),
+                op: op(Word {
+                    parts: vec![literal(pattern)],
+                }),
+            })])
+        };
+
+        assert_eq!(
+            tokenize("${F#*.} ${F##*.} ${F%.*} ${F%%.*}").unwrap(),
+            vec![
+                removal(ParameterOp::RemovePrefix, "*."),
+                removal(ParameterOp::RemoveLongestPrefix, "*."),
+                removal(ParameterOp::RemoveSuffix, ".*"),
+                removal(ParameterOp::RemoveLongestSuffix, ".*"),
+            ]
+        );
+        assert_eq!(
+            tokenize("${F###}").unwrap(),
+            vec![removal(ParameterOp::RemoveLongestPrefix, "#")]
+        );
+    }
+
+    #[test]
+    fn command_substitution_skips_quoted_parentheses() {
+        let tokens = tokenize("$(echo ')')").unwrap();
+        let [Token::Word(word)] = tokens.as_slice() else {
+            panic!("expected one word, got {tokens:?}");
+        };
+
+        assert!(matches!(word.parts.as_slice(), [WordPart::CommandSubst(_)]));
+    }
+
+    #[test]
+    fn reports_unterminated_constructs() {
+        let cases = [
+            ("'a", "unterminated single quote"),
+            ("\"a", "unterminated double quote"),
+            ("`a", "unterminated backquote"),
+            ("a\\", "trailing backslash"),
+            ("$(a", "missing `)'"),
+            ("${a", "missing `}'"),
+            ("${a:-b", "missing `}'"),
+            ("$((1)", "missing `))'"),
+        ];
+
+        for (input, reason) in cases {
+            assert_eq!(
+                tokenize(input),
+                Err(ParseError::Incomplete(reason)),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn reports_bad_substitutions() {
+        assert_eq!(
+            tokenize("${a!}"),
+            Err(ParseError::BadSubstitution("${a!}".to_string()))
+        );
+        assert_eq!(
+            tokenize("${}"),
+            Err(ParseError::BadSubstitution("${}".to_string()))
+        );
+        assert_eq!(
+            tokenize("$((1)x"),
+            Err(ParseError::BadSubstitution("$((1)".to_string()))
+        );
+    }
+}
@@ -0,0 +1,855 @@
+pub mod ast;
+mod lexer;
+
+use std::fmt;
+
+use ast::{
+    AndOr, AndOrOp, Assignment, CaseArm, Command, CompoundCommand, List, ListItem, Pipeline,
+    Redirect, RedirectKind, Separator, SimpleCommand, Word, WordPart,
+};
+use lexer::{Operator, Token};
+
+use crate::{aliases::read_aliases, vars};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Input ended while a construct still expects more text
+    Incomplete(&'static str),
+    /// A token that does not fit where it was found
+    Unexpected(String),
+    /// A `${...}` expansion that cannot be understood
+    BadSubstitution(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Incomplete(reason) => {
+                write!(f, "syntax error: unexpected end of input ({reason})")
+            }
+            ParseError::Unexpected(token) => {
+                write!(f, "syntax error near unexpected token `{token}'")
+            }
+            ParseError::BadSubstitution(text) => write!(f, "{text}: bad substitution"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Split `NAME=value` into its name and value, if the word starts with an unquoted valid name
+fn split_assignment(word: &Word) -> Option<Assignment> {
+    let Some(WordPart::Literal(first)) = word.parts.first() else {
+        return None;
+    };
+
+    let (name, rest) = first.split_once('=')?;
+    if !vars::is_valid_name(name) {
+        return None;
+    }
+
+    let mut value = Word::default();
+    if !rest.is_empty() {
+        value.parts.push(WordPart::Literal(rest.to_string()));
+    }
+    value.parts.extend(word.parts[1..].iter().cloned());
+
+    Some(Assignment {
+        name: name.to_string(),
+        value,
+    })
+}
+
+/// Reserved words that close a compound command, an error anywhere else a command may start
+const CLOSING_WORDS: [&str; 8] = ["}", "then", "elif", "else", "fi", "do", "done", "esac"];
+
+/// Reserved words that open a compound command or a function definition
+const OPENING_WORDS: [&str; 7] = ["{", "if", "while", "until", "for", "case", "function"];
+
+/// Parse a command line into a list of commands
+pub fn parse(input: &str) -> Result<List, ParseError> {
+    let tokens = lexer::tokenize(input)?;
+    Parser {
+        tokens,
+        pos: 0,
+        alias_expansions: Vec::new(),
+    }
+    .parse_list(&[])
+}
+
+/// Whether the input stops in the middle of a command, which more lines could complete
+pub fn is_incomplete(input: &str) -> bool {
+    matches!(parse(input), Err(ParseError::Incomplete(_)))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Aliases being expanded, with the position right after the tokens of their value
+    alias_expansions: Vec<(String, usize)>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_operator(&self) -> Option<Operator> {
+        match self.peek() {
+            Some(Token::Operator(op)) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::Newline) {
+            self.pos += 1;
+        }
+    }
+
+    /// The next word if it is unquoted, which is when it may be a reserved word
+    fn peek_reserved(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Word(word)) => word.as_literal(),
+            _ => None,
+        }
+    }
+
+    /// Consume the reserved word `expected`, `missing` describes it if input ends first
+    fn expect_reserved(&mut self, expected: &str, missing: &'static str) -> Result<(), ParseError> {
+        match self.peek_reserved() {
+            Some(word) if word == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ if self.peek().is_none() => Err(ParseError::Incomplete(missing)),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(token) => ParseError::Unexpected(token.to_string()),
+            None => ParseError::Incomplete("missing command"),
+        }
+    }
+
+    /// Whether the next token is one of the `terminators`, reserved words or the `;;` of a case arm
+    fn at_terminator(&self, terminators: &[&str]) -> bool {
+        match self.peek() {
+            Some(Token::Operator(op)) => terminators.contains(&op.as_str()),
+            _ => self
+                .peek_reserved()
+                .is_some_and(|word| terminators.contains(&word)),
+        }
+    }
+
+    /// Parse commands until the end of input or one of the `terminators`
+    fn parse_list(&mut self, terminators: &[&str]) -> Result<List, ParseError> {
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_newlines();
+
+            if self.peek().is_none() || self.at_terminator(terminators) {
+                break;
+            }
+
+            let and_or = self.parse_and_or()?;
+
+            let separator = match self.peek() {
+                Some(Token::Operator(Operator::Semi)) | Some(Token::Newline) => {
+                    self.pos += 1;
+                    Separator::Sequential
+                }
+                Some(Token::Operator(Operator::Amp)) => {
+                    self.pos += 1;
+                    Separator::Background
+                }
+                None => Separator::Sequential,
+                Some(_) if self.at_terminator(terminators) => Separator::Sequential,
+                Some(_) => return Err(self.unexpected()),
+            };
+
+            items.push(ListItem { and_or, separator });
+        }
+
+        Ok(List { items })
+    }
+
+    fn parse_and_or(&mut self) -> Result<AndOr, ParseError> {
+        let first = self.parse_pipeline()?;
+        let mut rest = Vec::new();
+
+        loop {
+            let op = match self.peek_operator() {
+                Some(Operator::AndIf) => AndOrOp::And,
+                Some(Operator::OrIf) => AndOrOp::Or,
+                _ => break,
+            };
+
+            self.pos += 1;
+            self.skip_newlines();
+
+            if self.peek().is_none() {
+                return Err(ParseError::Incomplete("missing command after operator"));
+            }
+
+            rest.push((op, self.parse_pipeline()?));
+        }
+
+        Ok(AndOr { first, rest })
+    }
+
+    fn parse_pipeline(&mut self) -> Result<Pipeline, ParseError> {
+        let mut commands = vec![self.parse_command()?];
+
+        while self.peek_operator() == Some(Operator::Pipe) {
+            self.pos += 1;
+            self.skip_newlines();
+
+            if self.peek().is_none() {
+                return Err(ParseError::Incomplete("missing command after pipe"));
+            }
+
+            commands.push(self.parse_command()?);
+        }
+
+        Ok(Pipeline { commands })
+    }
+
+    fn parse_command(&mut self) -> Result<Command, ParseError> {
+        // An alias may expand to a reserved word, so it is replaced first
+        let alias_at = self.expand_alias()?;
+
+        match self.peek_reserved() {
+            Some("{") => {
+                self.pos += 1;
+                let body = self.parse_list(&["}"])?;
+                self.expect_reserved("}", "missing `}'")?;
+                self.parse_compound(CompoundCommand::Group(body))
+            }
+            Some("if") => self.parse_if(),
+            Some(keyword @ ("while" | "until")) => {
+                let until = keyword == "until";
+                self.pos += 1;
+                let condition = self.parse_body(&["do"], "missing `do'")?;
+                let body = self.parse_do_group()?;
+
+                self.parse_compound(if until {
+                    CompoundCommand::Until { condition, body }
+                } else {
+                    CompoundCommand::While { condition, body }
+                })
+            }
+            Some("for") => self.parse_for(),
+            Some("case") => self.parse_case(),
+            Some("function") => {
+                self.pos += 1;
+
+                let name = match self.bump() {
+                    Some(Token::Word(word)) => match word.as_literal() {
+                        Some(name) => name.to_string(),
+                        None => return Err(ParseError::Unexpected(word.to_unquoted())),
+                    },
+                    Some(token) => return Err(ParseError::Unexpected(token.to_string())),
+                    None => return Err(ParseError::Incomplete("missing function name")),
+                };
+
+                // The parentheses are optional after the keyword
+                if self.peek_operator() == Some(Operator::LParen) {
+                    self.pos += 1;
+                    self.expect_operator(Operator::RParen)?;
+                }
+
+                self.parse_function_body(name)
+            }
+            Some(word) if CLOSING_WORDS.contains(&word) => Err(self.unexpected()),
+            Some(name)
+                if self.tokens.get(self.pos + 1) == Some(&Token::Operator(Operator::LParen)) =>
+            {
+                let name = name.to_string();
+                self.pos += 2;
+                self.expect_operator(Operator::RParen)?;
+                self.parse_function_body(name)
+            }
+            _ => self.parse_simple_command(alias_at),
+        }
+    }
+
+    fn expect_operator(&mut self, expected: Operator) -> Result<(), ParseError> {
+        match self.peek_operator() {
+            Some(op) if op == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Parse the compound command a function runs, after its name
+    fn parse_function_body(&mut self, name: String) -> Result<Command, ParseError> {
+        self.skip_newlines();
+
+        match self.peek_reserved() {
+            Some("{" | "if" | "while" | "until" | "for" | "case") => {
+                let body = self.parse_command()?;
+                Ok(Command::FunctionDef(name, Box::new(body)))
+            }
+            _ if self.peek().is_none() => Err(ParseError::Incomplete("missing function body")),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Parse a list that must contain at least one command, then its closing reserved word
+    fn parse_body(
+        &mut self,
+        terminators: &[&str],
+        missing: &'static str,
+    ) -> Result<List, ParseError> {
+        let body = self.parse_list(terminators)?;
+
+        if self.peek().is_none() {
+            return Err(ParseError::Incomplete(missing));
+        }
+        if body.items.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        Ok(body)
+    }
+
+    /// Parse `do list; done`, after the `do` is the next token
+    fn parse_do_group(&mut self) -> Result<List, ParseError> {
+        self.expect_reserved("do", "missing `do'")?;
+        let body = self.parse_body(&["done"], "missing `done'")?;
+        self.expect_reserved("done", "missing `done'")?;
+
+        Ok(body)
+    }
+
+    fn parse_if(&mut self) -> Result<Command, ParseError> {
+        let mut branches = Vec::new();
+        let mut else_body = None;
+
+        // Either `if` or an `elif`
+        self.pos += 1;
+
+        loop {
+            let condition = self.parse_body(&["then"], "missing `then'")?;
+            self.expect_reserved("then", "missing `then'")?;
+            let body = self.parse_body(&["elif", "else", "fi"], "missing `fi'")?;
+            branches.push((condition, body));
+
+            match self.peek_reserved() {
+                Some("elif") => self.pos += 1,
+                Some("else") => {
+                    self.pos += 1;
+                    else_body = Some(self.parse_body(&["fi"], "missing `fi'")?);
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        self.expect_reserved("fi", "missing `fi'")?;
+        self.parse_compound(CompoundCommand::If {
+            branches,
+            else_body,
+        })
+    }
+
+    fn parse_for(&mut self) -> Result<Command, ParseError> {
+        self.pos += 1;
+
+        let name = match self.bump() {
+            Some(Token::Word(word)) => match word.as_literal() {
+                Some(name) if vars::is_valid_name(name) => name.to_string(),
+                _ => return Err(ParseError::Unexpected(word.to_unquoted())),
+            },
+            Some(token) => return Err(ParseError::Unexpected(token.to_string())),
+            None => return Err(ParseError::Incomplete("missing loop variable")),
+        };
+
+        if self.peek_operator() == Some(Operator::Semi) {
+            self.pos += 1;
+        }
+        self.skip_newlines();
+
+        let mut words = None;
+        if self.peek_reserved() == Some("in") {
+            self.pos += 1;
+            let mut list = Vec::new();
+
+            while let Some(Token::Word(word)) = self.peek() {
+                list.push(word.clone());
+                self.pos += 1;
+            }
+
+            match self.peek() {
+                Some(Token::Operator(Operator::Semi)) | Some(Token::Newline) => self.pos += 1,
+                None => return Err(ParseError::Incomplete("missing `do'")),
+                Some(_) => return Err(self.unexpected()),
+            }
+            words = Some(list);
+        }
+        self.skip_newlines();
+
+        let body = self.parse_do_group()?;
+        self.parse_compound(CompoundCommand::For { name, words, body })
+    }
+
+    fn parse_case(&mut self) -> Result<Command, ParseError> {
+        self.pos += 1;
+
+        let word = match self.bump() {
+            Some(Token::Word(word)) => word,
+            Some(token) => return Err(ParseError::Unexpected(token.to_string())),
+            None => return Err(ParseError::Incomplete("missing case word")),
+        };
+
+        self.skip_newlines();
+        self.expect_reserved("in", "missing `in'")?;
+
+        let mut arms = Vec::new();
+
+        loop {
+            self.skip_newlines();
+
+            if self.peek_reserved() == Some("esac") {
+                break;
+            }
+
+            if self.peek_operator() == Some(Operator::LParen) {
+                self.pos += 1;
+            }
+
+            let mut patterns = Vec::new();
+            loop {
+                match self.bump() {
+                    Some(Token::Word(pattern)) => patterns.push(pattern),
+                    Some(token) => return Err(ParseError::Unexpected(token.to_string())),
+                    None => return Err(ParseError::Incomplete("missing `esac'")),
+                }
+
+                match self.bump() {
+                    Some(Token::Operator(Operator::Pipe)) => continue,
+                    Some(Token::Operator(Operator::RParen)) => break,
+                    Some(token) => return Err(ParseError::Unexpected(token.to_string())),
+                    None => return Err(ParseError::Incomplete("missing `esac'")),
+                }
+            }
+
+            let body = self.parse_list(&[";;", "esac"])?;
+            arms.push(CaseArm { patterns, body });
+
+            match self.peek() {
+                Some(Token::Operator(Operator::DSemi)) => self.pos += 1,
+                // The last arm may omit its `;;`
+                Some(_) => break,
+                None => return Err(ParseError::Incomplete("missing `esac'")),
+            }
+        }
+
+        self.expect_reserved("esac", "missing `esac'")?;
+        self.parse_compound(CompoundCommand::Case { word, arms })
+    }
+
+    /// Collect the redirections that may follow a compound command
+    fn parse_compound(&mut self, compound: CompoundCommand) -> Result<Command, ParseError> {
+        let mut redirects = Vec::new();
+
+        while let Some(redirect) = self.parse_redirect()? {
+            redirects.push(redirect);
+        }
+
+        Ok(Command::Compound(compound, redirects))
+    }
+
+    /// Replace an alias at the next token by the tokens of its value, again and again
+    ///
+    /// An alias is not expanded again within its own expansion, which stops
+    /// `alias ls='ls -F'` from looping. When the last value ends with a blank, returns the
+    /// position of the word after it, which is an alias candidate too.
+    fn expand_alias(&mut self) -> Result<Option<usize>, ParseError> {
+        let pos = self.pos;
+        self.alias_expansions.retain(|(_, end)| pos < *end);
+
+        let mut next_candidate = None;
+
+        while let Some(name) = self.peek_reserved()
+            && !OPENING_WORDS.contains(&name)
+            && !CLOSING_WORDS.contains(&name)
+            && !self
+                .alias_expansions
+                .iter()
+                .any(|(active, _)| active == name)
+            && let Some(value) = read_aliases()
+                .ok()
+                .and_then(|aliases| aliases.get(name).map(str::to_string))
+        {
+            let name = name.to_string();
+            let tokens = lexer::tokenize(&value)?;
+            let len = tokens.len();
+            self.tokens.splice(self.pos..self.pos + 1, tokens);
+
+            // Enclosing expansions now end further away, or closer for an empty value
+            for (_, end) in &mut self.alias_expansions {
+                *end = *end + len - 1;
+            }
+            self.alias_expansions.push((name, self.pos + len));
+
+            next_candidate = value.ends_with([' ', '\t']).then_some(self.pos + len);
+        }
+
+        Ok(next_candidate)
+    }
+
+    /// Whether the parser stands right at the end of an alias expansion, as after an empty one
+    fn after_alias(&self) -> bool {
+        self.alias_expansions
+            .iter()
+            .any(|(_, end)| *end == self.pos)
+    }
+
+    /// Parse a simple command, `alias_at` is where a word may still be an alias
+    fn parse_simple_command(&mut self, mut alias_at: Option<usize>) -> Result<Command, ParseError> {
+        let mut command = SimpleCommand::default();
+
+        loop {
+            match self.peek() {
+                Some(Token::Word(_)) => {
+                    if alias_at == Some(self.pos) {
+                        alias_at = self.expand_alias()?;
+
+                        if !matches!(self.peek(), Some(Token::Word(_))) {
+                            continue;
+                        }
+                    }
+
+                    if let Some(Token::Word(word)) = self.bump() {
+                        // Assignments are only recognized before the command name
+                        match split_assignment(&word) {
+                            Some(assignment) if command.words.is_empty() => {
+                                // The command name may still be an alias
+                                alias_at = Some(self.pos);
+                                command.assignments.push(assignment)
+                            }
+                            _ => command.words.push(word),
+                        }
+                    }
+                }
+                Some(Token::IoNumber(_)) | Some(Token::Operator(_)) => {
+                    match self.parse_redirect()? {
+                        Some(redirect) => command.redirects.push(redirect),
+                        None => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        // An alias with an empty value leaves an empty command
+        if command.assignments.is_empty()
+            && command.words.is_empty()
+            && command.redirects.is_empty()
+            && !self.after_alias()
+        {
+            return Err(self.unexpected());
+        }
+
+        Ok(Command::Simple(command))
+    }
+    /// Parse a redirection, or return `None` if the next token does not start one
+    fn parse_redirect(&mut self) -> Result<Option<Redirect>, ParseError> {
+        let start = self.pos;
+
+        let fd = match self.peek() {
+            Some(Token::IoNumber(fd)) => {
+                let fd = *fd;
+                self.pos += 1;
+                Some(fd)
+            }
+            _ => None,
+        };
+
+        let kind = match self.peek_operator() {
+            Some(Operator::Less) => RedirectKind::Input,
+            Some(Operator::Great) => RedirectKind::Output,
+            Some(Operator::DGreat) => RedirectKind::Append,
+            Some(Operator::LessAnd) => RedirectKind::DupInput,
+            Some(Operator::GreatAnd) => RedirectKind::DupOutput,
+            Some(Operator::AndGreat) if fd.is_none() => RedirectKind::OutputAll,
+            Some(Operator::AndDGreat) if fd.is_none() => RedirectKind::AppendAll,
+            _ if fd.is_some() => return Err(self.unexpected()),
+            _ => {
+                self.pos = start;
+                return Ok(None);
+            }
+        };
+
+        self.pos += 1;
+
+        match self.bump() {
+            Some(Token::Word(target)) => Ok(Some(Redirect { fd, kind, target })),
+            Some(token) => Err(ParseError::Unexpected(token.to_string())),
+            None => Err(ParseError::Unexpected(Token::Newline.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(text: &str) -> Word {
+        Word {
+            parts: vec![WordPart::Literal(text.to_string())],
+        }
+    }
+
+    /// The only simple command of the input
+    fn simple(input: &str) -> SimpleCommand {
+        let list = parse(input).unwrap();
+
+        match list.items.as_slice() {
+            [item] if item.and_or.rest.is_empty() => match item.and_or.first.commands.as_slice() {
+                [Command::Simple(command)] => command.clone(),
+                commands => panic!("expected one simple command, got {commands:?}"),
+            },
+            items => panic!("expected one command, got {items:?}"),
+        }
+    }
+
+    fn unexpected(token: &str) -> Result<List, ParseError> {
+        Err(ParseError::Unexpected(token.to_string()))
+    }
+
+    #[test]
+    fn parses_assignments_words_and_redirects() {
+        let command = simple("A=1 B= echo a=b >out 2>&1");
+
+        assert_eq!(
+            command.assignments,
+            vec![
+                Assignment {
+                    name: "A".to_string(),
+                    value: literal("1"),
+                },
+                Assignment {
+                    name: "B".to_string(),
+                    value: Word::default(),
+                },
+            ]
+        );
+        assert_eq!(command.words, vec![literal("echo"), literal("a=b")]);
+        assert_eq!(
+            command.redirects,
+            vec![
+                Redirect {
+                    fd: None,
+                    kind: RedirectKind::Output,
+                    target: literal("out"),
+                },
+                Redirect {
+                    fd: Some(2),
+                    kind: RedirectKind::DupOutput,
+                    target: literal("1"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_redirect_kinds() {
+        let kinds: Vec<RedirectKind> = simple("a <i >o >>p <&3 >&4 &>q &>>r")
+            .redirects
+            .into_iter()
+            .map(|redirect| redirect.kind)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                RedirectKind::Input,
+                RedirectKind::Output,
+                RedirectKind::Append,
+                RedirectKind::DupInput,
+                RedirectKind::DupOutput,
+                RedirectKind::OutputAll,
+                RedirectKind::AppendAll,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_separators_and_operators() {
+        let list = parse("a && b || c | d; e &\nf").unwrap();
+
+        let separators: Vec<Separator> = list.items.iter().map(|item| item.separator).collect();
+        assert_eq!(
+            separators,
+            [
+                Separator::Sequential,
+                Separator::Background,
+                Separator::Sequential,
+            ]
+        );
+
+        let first = &list.items[0].and_or;
+        let ops: Vec<AndOrOp> = first.rest.iter().map(|(op, _)| *op).collect();
+        assert_eq!(ops, [AndOrOp::And, AndOrOp::Or]);
+        assert_eq!(first.rest[1].1.commands.len(), 2);
+    }
+
+    #[test]
+    fn parses_compound_commands() {
+        let inputs = [
+            "{ a; }",
+            "if a; then b; elif c; then d; else e; fi",
+            "while a; do b; done",
+            "until a\ndo\nb\ndone",
+            "for x in a b; do c; done",
+            "for x; do c; done",
+            "case a in x|y) b;; (z) c; esac",
+            "case a in esac",
+            "f() { a; }",
+            "function f { a; }",
+            "{ a; } >out",
+        ];
+
+        for input in inputs {
+            assert!(parse(input).is_ok(), "{input}");
+        }
+    }
+
+    #[test]
+    fn displays_parsed_commands_back() {
+        let inputs = [
+            "echo 'a b' \"$X\" >out",
+            "if a; then b; else c; fi",
+            "for x in a b; do c; done",
+            "echo ${F#*.} ${F##*.} ${F%.*} ${F%%.*}",
+        ];
+
+        for input in inputs {
+            let list = parse(input).unwrap();
+            assert_eq!(parse(&list.to_string()), Ok(list), "{input}");
+        }
+    }
+
+    #[test]
+    fn heredocs_are_not_supported() {
+        assert_eq!(parse("cat <<EOF"), unexpected("<"));
+    }
+
+    #[test]
+    fn reports_redirect_errors() {
+        assert_eq!(parse("echo >"), unexpected("newline"));
+        assert_eq!(parse("echo > | a"), unexpected("|"));
+        assert_eq!(parse("echo 2>>&1"), unexpected("&"));
+        assert_eq!(parse("echo >;"), unexpected(";"));
+    }
+
+    #[test]
+    fn reports_unexpected_tokens() {
+        let cases = [
+            ("echo )", ")"),
+            ("; a", ";"),
+            ("a |;", ";"),
+            ("fi", "fi"),
+            ("if then a; fi", "then"),
+            ("if a; then fi", "fi"),
+            ("while a; do done", "done"),
+            ("for 1 in a; do b; done", "1"),
+            ("for x in a | b", "|"),
+            ("case a in x b) c;; esac", "b"),
+            ("f() a", "a"),
+            ("f(x) { a; }", "x"),
+            ("{ a; } b", "b"),
+        ];
+
+        for (input, token) in cases {
+            assert_eq!(parse(input), unexpected(token), "{input}");
+        }
+    }
+
+    #[test]
+    fn reports_bad_substitutions() {
+        assert_eq!(
+            parse("echo ${a%%b"),
+            Err(ParseError::Incomplete("missing `}'"))
+        );
+        assert_eq!(
+            parse("echo ${#a:-b}"),
+            Err(ParseError::BadSubstitution("${#a:-b}".to_string()))
+        );
+    }
+
+    #[test]
+    fn detects_incomplete_input() {
+        let cases = [
+            ("echo 'a", "unterminated single quote"),
+            ("echo \"a", "unterminated double quote"),
+            ("echo `a", "unterminated backquote"),
+            ("echo a\\", "trailing backslash"),
+            ("echo $(a", "missing `)'"),
+            ("echo ${a", "missing `}'"),
+            ("echo $((1", "missing `)'"),
+            ("a &&", "missing command after operator"),
+            ("a ||\n", "missing command after operator"),
+            ("a |", "missing command after pipe"),
+            ("{ a", "missing `}'"),
+            ("if a", "missing `then'"),
+            ("if a; then b", "missing `fi'"),
+            ("if a; then b; elif c", "missing `then'"),
+            ("if a; then b; else c", "missing `fi'"),
+            ("while a", "missing `do'"),
+            ("until a; do b", "missing `done'"),
+            ("for", "missing loop variable"),
+            ("for x", "missing `do'"),
+            ("for x in a b", "missing `do'"),
+            ("case", "missing case word"),
+            ("case a", "missing `in'"),
+            ("case a in", "missing `esac'"),
+            ("case a in x", "missing `esac'"),
+            ("case a in x) b", "missing `esac'"),
+            ("function", "missing function name"),
+            ("f()", "missing function body"),
+        ];
+
+        for (input, reason) in cases {
+            assert_eq!(parse(input), Err(ParseError::Incomplete(reason)), "{input}");
+            assert!(is_incomplete(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn complete_or_invalid_input_is_not_incomplete() {
+        for input in ["", "echo a", "if a; then b; fi", "echo )", "a\\\nb", "${}"] {
+            assert!(!is_incomplete(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn displays_errors() {
+        assert_eq!(
+            ParseError::Incomplete("missing `fi'").to_string(),
+            "syntax error: unexpected end of input (missing `fi')"
+        );
+        assert_eq!(
+            ParseError::Unexpected(")".to_string()).to_string(),
+            "syntax error near unexpected token `)'"
+        );
+        assert_eq!(
+            ParseError::BadSubstitution("${a!}".to_string()).to_string(),
+            "${a!}: bad substitution"
+        );
+    }
+}
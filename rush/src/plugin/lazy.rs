@@ -41,6 +41,17 @@ pub fn get_plugin(name: &str) -> anyhow::Result<Arc<CommandRef>> {
     plugin.ok_or_else(|| anyhow::anyhow!("{}: plugin failed to load", name))
 }
 
+pub fn is_plugin(name: &str) -> bool {
+    read_plugin_registry().is_ok_and(|registry| registry.borrow_ref(name).is_some())
+}
+
+/// Names of every discovered plugin, loaded or not
+pub fn plugin_names() -> Vec<String> {
+    read_plugin_registry()
+        .map(|registry| registry.names().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
 // pub fn reload_plugin(name: &str) -> anyhow::Result<Arc<CommandRef>> {
 //     let mut registry_writer = write_plugin_registry()?;
 //
@@ -9,7 +9,7 @@ use std::{
 use anyhow::ensure;
 use rush_interface::CommandRef;
 
-pub use lazy::get_plugin;
+pub use lazy::{get_plugin, is_plugin, plugin_names};
 
 struct PluginMetadata {
     name: String,
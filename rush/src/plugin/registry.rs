@@ -29,6 +29,10 @@ impl PluginRegistry {
     pub fn borrow_ref(&self, name: &str) -> Option<&PluginMetadata> {
         self.registry.get(name)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.registry.keys().map(String::as_str)
+    }
 }
 
 fn plugin_registry() -> &'static RwLock<PluginRegistry> {
@@ -0,0 +1,178 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS, quote},
+};
+use crate::{
+    aliases,
+    input::{read_abbrs, write_abbrs},
+};
+
+static BUILTIN_NAME: &str = "abbr";
+static DESC_STRING: &str =
+    "Manage abbreviations, expanded in place as they are typed.\nabbr is a shell built-in";
+
+pub(super) struct Command;
+
+enum Action {
+    Add,
+    Erase,
+    Show,
+    List,
+}
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!(
+            "Usage: {} [-h | -v | -s | -l] [-a name expansion... | -e name...]",
+            BUILTIN_NAME
+        );
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            (
+                "-a, --add",
+                "Adds an abbreviation, the default with arguments",
+            ),
+            ("-e, --erase", "Removes each abbreviation"),
+            (
+                "-s, --show",
+                "Lists all abbreviations, the default without arguments",
+            ),
+            ("-l, --list", "Lists the names of all abbreviations"),
+        ];
+
+        let examples = [
+            format!("{} -a gco git checkout", BUILTIN_NAME),
+            format!("{} -e gco", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut action = None;
+        let mut operands = Vec::new();
+
+        for arg in args.iter() {
+            // Options end at the first operand, the expansion may have options of its own
+            if !operands.is_empty() {
+                operands.push(arg.as_str());
+                continue;
+            }
+
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-a" | "--add" => action = Some(Action::Add),
+                "-e" | "--erase" => action = Some(Action::Erase),
+                "-s" | "--show" => action = Some(Action::Show),
+                "-l" | "--list" => action = Some(Action::List),
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                operand => operands.push(operand),
+            }
+        }
+
+        let action = action.unwrap_or(if operands.is_empty() {
+            Action::Show
+        } else {
+            Action::Add
+        });
+
+        match action {
+            Action::Show | Action::List => match read_abbrs() {
+                Ok(abbrs) => {
+                    for (name, expansion) in abbrs.iter() {
+                        match action {
+                            Action::Show => {
+                                println!("{BUILTIN_NAME} -a {name} {}", quote(expansion))
+                            }
+                            _ => println!("{name}"),
+                        }
+                    }
+                    ExecResult::ok()
+                }
+                Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            },
+            Action::Add => {
+                let [name, expansion @ ..] = operands.as_slice() else {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: -a: requires a name and an expansion"),
+                    );
+                };
+
+                if expansion.is_empty() {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {name}: missing expansion"),
+                    );
+                }
+                // Abbreviations are typed where a command name goes, as aliases are
+                if !aliases::is_valid_name(name) {
+                    return ExecResult::new(
+                        EXIT_FAILURE,
+                        &format!("{BUILTIN_NAME}: `{name}': invalid abbreviation name"),
+                    );
+                }
+
+                match write_abbrs() {
+                    Ok(mut abbrs) => {
+                        abbrs.define(name, &expansion.join(" "));
+                        ExecResult::ok()
+                    }
+                    Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+                }
+            }
+            Action::Erase => {
+                let mut abbrs = match write_abbrs() {
+                    Ok(abbrs) => abbrs,
+                    Err(e) => {
+                        return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}"));
+                    }
+                };
+                let mut status = ExecResult::ok();
+
+                for name in operands {
+                    if !abbrs.remove(name) {
+                        eprintln!("{BUILTIN_NAME}: {name}: not found");
+                        status = ExecResult::new(EXIT_FAILURE, "");
+                    }
+                }
+
+                status
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS, quote},
+};
+use crate::aliases::{self, read_aliases, write_aliases};
+
+static BUILTIN_NAME: &str = "alias";
+static DESC_STRING: &str = "Define or display aliases.\nalias is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -p] [name[=value]...]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-p", "Lists all aliases"),
+        ];
+
+        let examples = [
+            format!("{} ll='ls -l'", BUILTIN_NAME),
+            format!("{} ll", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut names = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-p" => {}
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                name => names.push(name),
+            }
+        }
+
+        if names.is_empty() {
+            return match read_aliases() {
+                Ok(aliases) => {
+                    for (name, value) in aliases.iter() {
+                        println!("{BUILTIN_NAME} {name}={}", quote(value));
+                    }
+                    ExecResult::ok()
+                }
+                Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            };
+        }
+
+        let mut aliases = match write_aliases() {
+            Ok(aliases) => aliases,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+        let mut status = ExecResult::ok();
+
+        for arg in names {
+            match arg.split_once('=') {
+                Some((name, value)) if aliases::is_valid_name(name) => aliases.define(name, value),
+                Some((name, _)) => {
+                    eprintln!("{BUILTIN_NAME}: `{name}': invalid alias name");
+                    status = ExecResult::new(EXIT_FAILURE, "");
+                }
+                None => match aliases.get(arg) {
+                    Some(value) => println!("{BUILTIN_NAME} {arg}={}", quote(value)),
+                    None => {
+                        eprintln!("{BUILTIN_NAME}: {arg}: not found");
+                        status = ExecResult::new(EXIT_FAILURE, "");
+                    }
+                },
+            }
+        }
+
+        status
+    }
+}
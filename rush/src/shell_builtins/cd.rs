@@ -0,0 +1,190 @@
+use std::{
+    env,
+    path::{Component, Path, PathBuf},
+};
+
+use abi_stable::std_types::{RString, RVec};
+use nix::unistd::chdir;
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::vars::{read_vars, write_vars};
+
+static BUILTIN_NAME: &str = "cd";
+static DESC_STRING: &str = "Change the shell working directory.\ncd is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -L | -P] [dir | -]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            (
+                "-L",
+                "Follows symbolic links, resolving `..' against $PWD (default)",
+            ),
+            (
+                "-P",
+                "Uses the physical directory structure, resolving symbolic links",
+            ),
+        ];
+
+        let examples = [
+            format!("{} ~/src", BUILTIN_NAME),
+            format!("{} -", BUILTIN_NAME),
+            format!("{} -P ..", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut physical = false;
+        let mut operands = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-L" => physical = false,
+                "-P" => physical = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                dir => operands.push(dir),
+            }
+        }
+
+        let (target, print) = match operands.as_slice() {
+            [] => match var("HOME") {
+                Some(home) => (home, false),
+                None => return ExecResult::new(EXIT_FAILURE, "cd: HOME not set"),
+            },
+            ["-"] => match var("OLDPWD") {
+                Some(oldpwd) => (oldpwd, true),
+                None => return ExecResult::new(EXIT_FAILURE, "cd: OLDPWD not set"),
+            },
+            [dir] => (dir.to_string(), false),
+            _ => return ExecResult::new(EXIT_FAILURE, "cd: too many arguments"),
+        };
+
+        if target.is_empty() {
+            return ExecResult::ok();
+        }
+
+        let (dir, found_in_cdpath) = search_cdpath(&target);
+
+        match change_dir(&dir, physical) {
+            Ok(pwd) => {
+                if print || found_in_cdpath {
+                    println!("{}", pwd.display());
+                }
+                ExecResult::ok()
+            }
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {target}: {e}")),
+        }
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    read_vars()
+        .ok()?
+        .get(name)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Look a relative directory up in `$CDPATH`, telling whether it was found through a listed entry
+fn search_cdpath(target: &str) -> (PathBuf, bool) {
+    let first = Path::new(target).components().next();
+    let searchable = matches!(first, Some(Component::Normal(_)));
+
+    if let Some(cdpath) = var("CDPATH").filter(|_| searchable) {
+        for entry in cdpath.split(':') {
+            let base = if entry.is_empty() { "." } else { entry };
+            let candidate = Path::new(base).join(target);
+
+            if candidate.is_dir() {
+                return (candidate, !entry.is_empty());
+            }
+        }
+    }
+
+    (PathBuf::from(target), false)
+}
+
+/// Change directory and update `PWD` and `OLDPWD`, returning the new `PWD`
+pub(super) fn change_dir(dir: &Path, physical: bool) -> anyhow::Result<PathBuf> {
+    let old_pwd = var("PWD")
+        .map(PathBuf::from)
+        .filter(|pwd| pwd.is_absolute())
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("/"));
+
+    let logical = normalize(&old_pwd.join(dir));
+
+    let pwd = if !physical && chdir(&logical).is_ok() {
+        logical
+    } else {
+        // A logical path that cannot be entered falls back to the physical one
+        chdir(dir).map_err(|e| anyhow::anyhow!(e.desc()))?;
+        env::current_dir()?
+    };
+
+    let mut vars = write_vars()?;
+    vars.set("OLDPWD", &old_pwd.to_string_lossy());
+    vars.set("PWD", &pwd.to_string_lossy());
+    vars.export("OLDPWD");
+    vars.export("PWD");
+
+    Ok(pwd)
+}
+
+/// Remove `.` and `..` components of an absolute path without resolving symbolic links
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::from("/");
+
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(name) => normalized.push(name),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+
+    normalized
+}
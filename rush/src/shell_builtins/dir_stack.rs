@@ -0,0 +1,84 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::vars::read_vars;
+
+/// Directories saved by `pushd`, most recent first; the working directory is not part of it
+static DIR_STACK: OnceLock<RwLock<Vec<PathBuf>>> = OnceLock::new();
+
+fn dir_stack() -> &'static RwLock<Vec<PathBuf>> {
+    DIR_STACK.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+pub(super) fn read_dir_stack() -> anyhow::Result<RwLockReadGuard<'static, Vec<PathBuf>>> {
+    dir_stack()
+        .read()
+        .map_err(|_| anyhow::anyhow!("DIR_STACK read lock poisoned"))
+}
+
+pub(super) fn write_dir_stack() -> anyhow::Result<RwLockWriteGuard<'static, Vec<PathBuf>>> {
+    dir_stack()
+        .write()
+        .map_err(|_| anyhow::anyhow!("DIR_STACK write lock poisoned"))
+}
+
+/// The working directory followed by the saved ones, as numbered by `dirs -v`
+pub(super) fn full_stack() -> anyhow::Result<Vec<PathBuf>> {
+    let pwd = read_vars()?
+        .get("PWD")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let mut stack = vec![pwd];
+    stack.extend(read_dir_stack()?.iter().cloned());
+
+    Ok(stack)
+}
+
+/// Position named by `+N` (from the left) or `-N` (from the right) in a stack of `len` entries
+pub(super) fn parse_position(arg: &str, len: usize) -> Option<usize> {
+    if let Some(n) = arg.strip_prefix('+') {
+        n.parse::<usize>().ok().filter(|&n| n < len)
+    } else if let Some(n) = arg.strip_prefix('-') {
+        let n = n.parse::<usize>().ok()?;
+        len.checked_sub(n + 1)
+    } else {
+        None
+    }
+}
+
+/// Whether an argument looks like `+N` or `-N`
+pub(super) fn is_position(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with(['+', '-']) && arg[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// A directory as `dirs` shows it, with the home directory abbreviated unless `long`
+pub(super) fn display(dir: &Path, long: bool) -> String {
+    let dir = dir.to_string_lossy();
+    let home = read_vars()
+        .ok()
+        .and_then(|vars| vars.get("HOME").map(str::to_string))
+        .filter(|home| !home.is_empty() && home != "/");
+
+    match home {
+        Some(home) if !long && dir == home => "~".to_string(),
+        Some(home) if !long && dir.starts_with(&format!("{home}/")) => {
+            format!("~{}", &dir[home.len()..])
+        }
+        _ => dir.into_owned(),
+    }
+}
+
+/// Print the whole stack on one line, as `pushd` and `popd` do after changing it
+pub(super) fn print_stack() -> anyhow::Result<()> {
+    let line = full_stack()?
+        .iter()
+        .map(|dir| display(dir, false))
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!("{line}");
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    dir_stack::{self, write_dir_stack},
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+
+static BUILTIN_NAME: &str = "dirs";
+static DESC_STRING: &str = "Display the directory stack.\ndirs is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!(
+            "Usage: {} [-h | --version | -c | -l | -p | -v] [+N | -N]",
+            BUILTIN_NAME
+        );
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("--version", "Prints the version"),
+            ("-c", "Clears the directory stack"),
+            (
+                "-l",
+                "Shows full paths instead of abbreviating the home directory with ~",
+            ),
+            ("-p", "Prints one entry per line"),
+            (
+                "-v",
+                "Prints one entry per line, numbered by its position in the stack",
+            ),
+            ("+N", "Shows the Nth entry from the left"),
+            ("-N", "Shows the Nth entry from the right"),
+        ];
+
+        let examples = [
+            format!("{} -v", BUILTIN_NAME),
+            format!("{} +1", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut clear = false;
+        let mut long = false;
+        let mut per_line = false;
+        let mut numbered = false;
+        let mut operands = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-c" => clear = true,
+                "-l" => long = true,
+                "-p" => per_line = true,
+                "-v" => numbered = true,
+                position if dir_stack::is_position(position) => operands.push(position),
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                operand => operands.push(operand),
+            }
+        }
+
+        if operands.len() > 1 {
+            return ExecResult::new(INVALID_ARGS, &format!("{BUILTIN_NAME}: too many arguments"));
+        }
+
+        if clear {
+            return match write_dir_stack() {
+                Ok(mut stack) => {
+                    stack.clear();
+                    ExecResult::ok()
+                }
+                Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            };
+        }
+
+        let stack = match dir_stack::full_stack() {
+            Ok(stack) => stack,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+
+        if let Some(position) = operands.first() {
+            return match dir_stack::parse_position(position, stack.len()) {
+                Some(index) => {
+                    println!("{}", dir_stack::display(&stack[index], long));
+                    ExecResult::ok()
+                }
+                None => ExecResult::new(
+                    EXIT_FAILURE,
+                    &format!("{BUILTIN_NAME}: {position}: directory stack index out of range"),
+                ),
+            };
+        }
+
+        let entries: Vec<String> = stack
+            .iter()
+            .map(|dir| dir_stack::display(dir, long))
+            .collect();
+
+        if numbered {
+            for (index, entry) in entries.iter().enumerate() {
+                println!("{index:2}  {entry}");
+            }
+        } else if per_line {
+            for entry in &entries {
+                println!("{entry}");
+            }
+        } else {
+            println!("{}", entries.join(" "));
+        }
+
+        ExecResult::ok()
+    }
+}
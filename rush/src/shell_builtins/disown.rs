@@ -0,0 +1,110 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::jobs::{self, read_job_table, write_job_table};
+
+static BUILTIN_NAME: &str = "disown";
+static DESC_STRING: &str = "Remove jobs from the job table.\ndisown is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -a] [jobspec...]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-a", "Removes all jobs"),
+        ];
+
+        let examples = [
+            format!("{} %1", BUILTIN_NAME),
+            format!("{} -a", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut all = false;
+        let mut specs = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-a" => all = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                spec => specs.push(spec),
+            }
+        }
+
+        jobs::update_jobs();
+
+        let ids = if all {
+            match read_job_table() {
+                Ok(table) => table.ids(),
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            }
+        } else if specs.is_empty() {
+            match jobs::resolve_job(None) {
+                Ok(id) => vec![id],
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            }
+        } else {
+            let resolved: anyhow::Result<Vec<usize>> = specs
+                .iter()
+                .map(|spec| jobs::resolve_job(Some(spec)))
+                .collect();
+
+            match resolved {
+                Ok(ids) => ids,
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            }
+        };
+
+        // Disowned jobs keep running, the shell just stops tracking them
+        match write_job_table() {
+            Ok(mut table) => {
+                for id in ids {
+                    table.remove(id);
+                }
+                ExecResult::ok()
+            }
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        }
+    }
+}
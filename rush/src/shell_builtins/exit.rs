@@ -4,6 +4,7 @@ use abi_stable::std_types::{RString, RVec};
 use rush_interface::ExecResult;
 
 use super::{BuiltinCommand, shared::INVALID_ARGS};
+use crate::vars::read_vars;
 
 static BUILTIN_NAME: &str = "exit";
 static DESC_STRING: &str = "Exit from current shell with code.\nexit is a shell built-in";
@@ -47,7 +48,8 @@ impl BuiltinCommand for Command {
 
     fn execute(&self, args: RVec<RString>) -> rush_interface::ExecResult {
         match args.as_slice() {
-            [] => process::exit(0),
+            // Without a code, the shell exits with the status of the last command
+            [] => process::exit(read_vars().map_or(0, |vars| vars.last_status()).into()),
 
             [param] => match param.as_str() {
                 "-h" => {
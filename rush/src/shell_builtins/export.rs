@@ -0,0 +1,123 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS, quote},
+};
+use crate::vars::{self, read_vars, write_vars};
+
+static BUILTIN_NAME: &str = "export";
+static DESC_STRING: &str = "Set export attribute for shell variables.\nexport is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!(
+            "Usage: {} [-h | -v | -p | -n] [name[=value]...]",
+            BUILTIN_NAME
+        );
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-p", "Lists all exported variables"),
+            ("-n", "Removes the export attribute from each name"),
+        ];
+
+        let examples = [
+            format!("{} EDITOR=vim", BUILTIN_NAME),
+            format!("{} -n PAGER", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut unexport = false;
+        let mut names = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-p" => {}
+                "-n" => unexport = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                name => names.push(name),
+            }
+        }
+
+        if names.is_empty() {
+            return match read_vars() {
+                Ok(vars) => {
+                    for (name, value) in vars.exported() {
+                        println!("{BUILTIN_NAME} {name}={}", quote(value));
+                    }
+                    ExecResult::ok()
+                }
+                Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            };
+        }
+
+        let mut vars = match write_vars() {
+            Ok(vars) => vars,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+        let mut status = ExecResult::ok();
+
+        for arg in names {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg, None),
+            };
+
+            if !vars::is_valid_name(name) {
+                eprintln!("{BUILTIN_NAME}: `{arg}': not a valid identifier");
+                status = ExecResult::new(EXIT_FAILURE, "");
+                continue;
+            }
+
+            if let Some(value) = value {
+                vars.set(name, value);
+            }
+
+            if unexport {
+                vars.unexport(name);
+            } else {
+                vars.export(name);
+            }
+        }
+
+        status
+    }
+}
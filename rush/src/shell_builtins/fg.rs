@@ -0,0 +1,81 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::jobs;
+
+static BUILTIN_NAME: &str = "fg";
+static DESC_STRING: &str = "Resume a job in the foreground.\nfg is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v] [jobspec]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+        ];
+
+        let examples = [BUILTIN_NAME.to_string(), format!("{} %2", BUILTIN_NAME)];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut specs = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                spec => specs.push(spec),
+            }
+        }
+
+        if specs.len() > 1 {
+            return ExecResult::new(INVALID_ARGS, &format!("{BUILTIN_NAME}: too many arguments"));
+        }
+
+        jobs::update_jobs();
+
+        match jobs::resolve_job(specs.first().copied()).and_then(|id| jobs::continue_job(id, true))
+        {
+            Ok(code) => ExecResult::new(code, ""),
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::{path::PathBuf, time::Duration};
+
+use abi_stable::std_types::{RString, RVec};
+use jiff::{
+    Span, Timestamp, Zoned,
+    civil::{Date, DateTime},
+    tz::TimeZone,
+};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    cd::normalize,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::history::{HistoryEntry, current_dir, read_history};
+
+static BUILTIN_NAME: &str = "history";
+static DESC_STRING: &str = "Display the command history.\nhistory is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!(
+            "Usage: {} [-h | -v | --version] [-d DIR] [-f] [--since TIME] [--until TIME] [N]",
+            BUILTIN_NAME
+        );
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            (
+                "-d, --dir DIR",
+                "Shows only commands entered in DIR, . for the current directory",
+            ),
+            ("-f, --failed", "Shows only commands that failed"),
+            (
+                "--since TIME",
+                "Shows only commands entered at or after TIME",
+            ),
+            ("--until TIME", "Shows only commands entered before TIME"),
+            ("N", "Shows only the last N matching commands"),
+        ];
+
+        let examples = [
+            format!("{} 20", BUILTIN_NAME),
+            format!("{} -d . -f", BUILTIN_NAME),
+            format!("{} --since 2h", BUILTIN_NAME),
+            format!(
+                "{} --since 2024-05-01 --until '2024-05-02 12:00'",
+                BUILTIN_NAME
+            ),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\n\
+             TIME is a local date as 2024-05-01, a local date and time as '2024-05-01 14:30',\n\
+             or how long ago as 30m, 2h, 3d or 1w.\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut dir = None;
+        let mut failed = false;
+        let mut since = None;
+        let mut until = None;
+        let mut count = None;
+
+        let mut args = args.iter().map(RString::as_str);
+
+        while let Some(arg) = args.next() {
+            match arg {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-f" | "--failed" => failed = true,
+                "-d" | "--dir" | "--since" | "--until" => {
+                    let Some(value) = args.next() else {
+                        return ExecResult::new(
+                            INVALID_ARGS,
+                            &format!("{BUILTIN_NAME}: {arg}: option requires an argument"),
+                        );
+                    };
+
+                    let parsed = match arg {
+                        "--since" => parse_time(value).map(|time| since = Some(time)),
+                        "--until" => parse_time(value).map(|time| until = Some(time)),
+                        _ => {
+                            dir = Some(resolve_dir(value));
+                            Ok(())
+                        }
+                    };
+
+                    if let Err(e) = parsed {
+                        return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}"));
+                    }
+                }
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                operand if count.is_some() => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {operand}: too many arguments"),
+                    );
+                }
+                operand => match operand.parse::<usize>() {
+                    Ok(n) => count = Some(n),
+                    Err(_) => {
+                        return ExecResult::new(
+                            INVALID_ARGS,
+                            &format!("{BUILTIN_NAME}: {operand}: numeric argument required"),
+                        );
+                    }
+                },
+            }
+        }
+
+        let history = match read_history() {
+            Ok(history) => history,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+
+        let matches: Vec<(usize, &HistoryEntry)> = history
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| dir.as_ref().is_none_or(|dir| entry.cwd == *dir))
+            .filter(|(_, entry)| !failed || entry.status != 0)
+            .filter(|(_, entry)| since.is_none_or(|since| entry.timestamp() >= since))
+            .filter(|(_, entry)| until.is_none_or(|until| entry.timestamp() < until))
+            .collect();
+
+        let skip = count.map_or(0, |n| matches.len().saturating_sub(n));
+        let tz = TimeZone::system();
+
+        for (index, entry) in &matches[skip..] {
+            println!(
+                "{:5}  {}  {:3}  {:>7}  {}",
+                index + 1,
+                entry
+                    .timestamp()
+                    .to_zoned(tz.clone())
+                    .strftime("%Y-%m-%d %H:%M:%S"),
+                entry.status,
+                format_duration(entry.duration()),
+                entry.command,
+            );
+        }
+
+        ExecResult::ok()
+    }
+}
+
+/// The directory commands were entered in, logical as the history records it
+fn resolve_dir(dir: &str) -> PathBuf {
+    normalize(&current_dir().join(dir))
+}
+
+/// A local date, a local date and time, or a span of time ago
+fn parse_time(text: &str) -> Result<Timestamp, String> {
+    let tz = TimeZone::system();
+    let invalid = || format!("{text}: invalid time");
+
+    // A blank between the date and the time reads as the ISO 8601 `T`
+    if let Ok(datetime) = text.replacen(' ', "T", 1).parse::<DateTime>() {
+        return datetime
+            .to_zoned(tz)
+            .map(|zoned| zoned.timestamp())
+            .map_err(|_| invalid());
+    }
+
+    if let Ok(date) = text.parse::<Date>() {
+        return date
+            .to_zoned(tz)
+            .map(|zoned| zoned.timestamp())
+            .map_err(|_| invalid());
+    }
+
+    let span = text.parse::<Span>().map_err(|_| invalid())?;
+    Zoned::now()
+        .checked_sub(span)
+        .map(|zoned| zoned.timestamp())
+        .map_err(|_| invalid())
+}
+
+/// A duration rounded to the unit that matters, as 12ms, 3.4s, 2m05s or 1h20m
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs == 0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
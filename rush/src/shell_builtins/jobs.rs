@@ -0,0 +1,125 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::jobs::{self, JobState, read_job_table, write_job_table};
+
+static BUILTIN_NAME: &str = "jobs";
+static DESC_STRING: &str = "Display status of jobs.\njobs is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -l | -p] [jobspec...]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-l", "Also lists the process group ID of each job"),
+            ("-p", "Lists only the process group ID of each job"),
+        ];
+
+        let examples = [
+            format!("{} -l", BUILTIN_NAME),
+            format!("{} %1", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut long = false;
+        let mut pids_only = false;
+        let mut specs = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-l" => long = true,
+                "-p" => pids_only = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                spec => specs.push(spec),
+            }
+        }
+
+        jobs::update_jobs();
+
+        let ids = if specs.is_empty() {
+            match read_job_table() {
+                Ok(table) => table.ids(),
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            }
+        } else {
+            let resolved: anyhow::Result<Vec<usize>> = specs
+                .iter()
+                .map(|spec| jobs::resolve_job(Some(spec)))
+                .collect();
+
+            match resolved {
+                Ok(ids) => ids,
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            }
+        };
+
+        let Ok(mut table) = write_job_table() else {
+            return ExecResult::new(
+                EXIT_FAILURE,
+                &format!("{BUILTIN_NAME}: job table unavailable"),
+            );
+        };
+
+        for id in ids {
+            let Some(job) = table.get(id) else {
+                continue;
+            };
+
+            if pids_only {
+                println!("{}", job.pgid);
+            } else {
+                println!("{}", table.describe(job, long));
+            }
+
+            // Finished jobs are forgotten once they have been listed
+            if matches!(job.state(), JobState::Done(_)) {
+                table.remove(id);
+            } else if let Some(job) = table.get_mut(id) {
+                job.notified = true;
+            }
+        }
+
+        ExecResult::ok()
+    }
+}
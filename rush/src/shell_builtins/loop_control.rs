@@ -0,0 +1,134 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::executor::{Flow, request_flow};
+
+/// `break` and `continue` only differ in what they ask of the loops they leave
+struct Keyword {
+    name: &'static str,
+    desc: &'static str,
+    option_desc: &'static str,
+    control: fn(usize) -> Flow,
+}
+
+static BREAK: Keyword = Keyword {
+    name: "break",
+    desc: "Exit for, while, or until loops.\nbreak is a shell built-in",
+    option_desc: "Number of enclosing loops to exit, 1 by default",
+    control: Flow::Break,
+};
+
+static CONTINUE: Keyword = Keyword {
+    name: "continue",
+    desc: "Resume the next iteration of for, while, or until loops.\ncontinue is a shell built-in",
+    option_desc: "Resume the n-th enclosing loop, 1 by default",
+    control: Flow::Continue,
+};
+
+pub(super) struct Break;
+
+pub(super) struct Continue;
+
+impl Keyword {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v] [n]", self.name);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("n", self.option_desc),
+        ];
+
+        let examples = [
+            format!("for f in *; do [ -d \"$f\" ] && {}; done", self.name),
+            format!(
+                "while true; do for i in 1 2; do {} 2; done; done",
+                self.name
+            ),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = self.desc,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut count = None;
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    println!("{}", env!("CARGO_PKG_VERSION"));
+                    return ExecResult::ok();
+                }
+                _ if count.is_some() => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{}: too many arguments", self.name),
+                    );
+                }
+                value => match value.parse::<usize>() {
+                    Ok(value) => count = Some(value),
+                    Err(_) => {
+                        return ExecResult::new(
+                            INVALID_ARGS,
+                            &format!("{}: {value}: numeric argument required", self.name),
+                        );
+                    }
+                },
+            }
+        }
+
+        match request_flow((self.control)(count.unwrap_or(1))) {
+            Ok(()) => ExecResult::ok(),
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{}: {e}", self.name)),
+        }
+    }
+}
+
+impl BuiltinCommand for Break {
+    fn print_help(&self) {
+        BREAK.print_help();
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        BREAK.execute(args)
+    }
+}
+
+impl BuiltinCommand for Continue {
+    fn print_help(&self) {
+        CONTINUE.print_help();
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        CONTINUE.execute(args)
+    }
+}
@@ -7,9 +7,28 @@ use abi_stable::std_types::{RString, RVec};
 use anyhow::bail;
 use rush_interface::ExecResult;
 
+mod abbr;
+mod alias;
+mod bg;
+mod cd;
+mod dir_stack;
+mod dirs;
+mod disown;
 mod exit;
+mod export;
+mod fg;
+mod history;
+mod jobs;
+mod local;
+mod loop_control;
 mod plugin;
+mod popd;
+mod pushd;
+mod r#return;
 mod shared;
+mod shopt;
+mod unalias;
+mod unset;
 
 static BUILTINS_REGISTRY: OnceLock<RwLock<BuiltinsRegistry>> = OnceLock::new();
 
@@ -42,6 +61,10 @@ impl BuiltinsRegistry {
         self.commands.contains_key(name)
     }
 
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
     pub fn execute(&self, builtin_name: &str, args: RVec<RString>) -> ExecResult {
         if let Some(command) = self.commands.get(builtin_name) {
             command.execute(args)
@@ -65,7 +88,26 @@ pub fn init_module() -> anyhow::Result<()> {
         .map_err(|e| anyhow::anyhow!("BUILTINS_REGISTRY write lock poisoned: {e}"))?;
 
     builtins.insert_command("exit", Arc::new(Box::new(exit::Command {})))?;
+    builtins.insert_command("cd", Arc::new(Box::new(cd::Command {})))?;
+    builtins.insert_command("pushd", Arc::new(Box::new(pushd::Command {})))?;
+    builtins.insert_command("popd", Arc::new(Box::new(popd::Command {})))?;
+    builtins.insert_command("dirs", Arc::new(Box::new(dirs::Command {})))?;
     builtins.insert_command("plugin", Arc::new(Box::new(plugin::Command {})))?;
+    builtins.insert_command("jobs", Arc::new(Box::new(jobs::Command {})))?;
+    builtins.insert_command("fg", Arc::new(Box::new(fg::Command {})))?;
+    builtins.insert_command("bg", Arc::new(Box::new(bg::Command {})))?;
+    builtins.insert_command("disown", Arc::new(Box::new(disown::Command {})))?;
+    builtins.insert_command("export", Arc::new(Box::new(export::Command {})))?;
+    builtins.insert_command("unset", Arc::new(Box::new(unset::Command {})))?;
+    builtins.insert_command("shopt", Arc::new(Box::new(shopt::Command {})))?;
+    builtins.insert_command("break", Arc::new(Box::new(loop_control::Break {})))?;
+    builtins.insert_command("continue", Arc::new(Box::new(loop_control::Continue {})))?;
+    builtins.insert_command("return", Arc::new(Box::new(r#return::Command {})))?;
+    builtins.insert_command("local", Arc::new(Box::new(local::Command {})))?;
+    builtins.insert_command("alias", Arc::new(Box::new(alias::Command {})))?;
+    builtins.insert_command("unalias", Arc::new(Box::new(unalias::Command {})))?;
+    builtins.insert_command("abbr", Arc::new(Box::new(abbr::Command {})))?;
+    builtins.insert_command("history", Arc::new(Box::new(history::Command {})))?;
 
     Ok(())
 }
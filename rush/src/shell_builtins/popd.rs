@@ -0,0 +1,112 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand, cd,
+    dir_stack::{self, write_dir_stack},
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+
+static BUILTIN_NAME: &str = "popd";
+static DESC_STRING: &str = "Remove directories from the directory stack.\npopd is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -n] [+N | -N]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-n", "Changes the stack without changing directory"),
+            ("+N", "Removes the Nth entry from the left"),
+            ("-N", "Removes the Nth entry from the right"),
+        ];
+
+        let examples = [BUILTIN_NAME.to_string(), format!("{} +1", BUILTIN_NAME)];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut no_cd = false;
+        let mut operands = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-n" => no_cd = true,
+                position if dir_stack::is_position(position) => operands.push(position),
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                operand => operands.push(operand),
+            }
+        }
+
+        if operands.len() > 1 {
+            return ExecResult::new(INVALID_ARGS, &format!("{BUILTIN_NAME}: too many arguments"));
+        }
+
+        let result = match operands.first() {
+            Some(position) if dir_stack::is_position(position) => pop(position, no_cd),
+            Some(operand) => Err(anyhow::anyhow!("{operand}: invalid argument")),
+            None => pop("+0", no_cd),
+        };
+
+        match result.and_then(|_| dir_stack::print_stack()) {
+            Ok(()) => ExecResult::ok(),
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        }
+    }
+}
+
+/// Remove the entry at `+N` or `-N`, changing to the next directory when it is the top one
+fn pop(position: &str, no_cd: bool) -> anyhow::Result<()> {
+    let stack = dir_stack::full_stack()?;
+
+    if stack.len() < 2 {
+        anyhow::bail!("directory stack empty");
+    }
+
+    let index = dir_stack::parse_position(position, stack.len())
+        .ok_or_else(|| anyhow::anyhow!("{position}: directory stack index out of range"))?;
+
+    if index == 0 && !no_cd {
+        let next = &stack[1];
+        cd::change_dir(next, false).map_err(|e| anyhow::anyhow!("{}: {e}", next.display()))?;
+    }
+
+    // With `-n`, popping the top removes the first saved entry instead
+    write_dir_stack()?.remove(index.max(1) - 1);
+
+    Ok(())
+}
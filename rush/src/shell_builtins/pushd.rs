@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand, cd,
+    dir_stack::{self, write_dir_stack},
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+
+static BUILTIN_NAME: &str = "pushd";
+static DESC_STRING: &str = "Add a directory to the directory stack.\npushd is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -n] [dir | +N | -N]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-n", "Changes the stack without changing directory"),
+            (
+                "+N",
+                "Rotates the stack so the Nth entry from the left is on top",
+            ),
+            (
+                "-N",
+                "Rotates the stack so the Nth entry from the right is on top",
+            ),
+        ];
+
+        let examples = [
+            format!("{} ~/src/rush", BUILTIN_NAME),
+            format!("{} +2", BUILTIN_NAME),
+            BUILTIN_NAME.to_string(),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut no_cd = false;
+        let mut operands = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-n" => no_cd = true,
+                position if dir_stack::is_position(position) => operands.push(position),
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                operand => operands.push(operand),
+            }
+        }
+
+        if operands.len() > 1 {
+            return ExecResult::new(INVALID_ARGS, &format!("{BUILTIN_NAME}: too many arguments"));
+        }
+
+        let result = match operands.first() {
+            Some(position) if dir_stack::is_position(position) => rotate(position, no_cd),
+            Some(dir) => push(dir, no_cd),
+            None => rotate("+1", no_cd),
+        };
+
+        match result.and_then(|_| dir_stack::print_stack()) {
+            Ok(()) => ExecResult::ok(),
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        }
+    }
+}
+
+/// Save the working directory and change to `dir`
+fn push(dir: &str, no_cd: bool) -> anyhow::Result<()> {
+    let old_pwd = dir_stack::full_stack()?.remove(0);
+
+    if no_cd {
+        // Saved as an absolute path, as it would be after changing to it
+        write_dir_stack()?.insert(0, cd::normalize(&old_pwd.join(dir)));
+        return Ok(());
+    }
+
+    cd::change_dir(Path::new(dir), false).map_err(|e| anyhow::anyhow!("{dir}: {e}"))?;
+    write_dir_stack()?.insert(0, old_pwd);
+
+    Ok(())
+}
+
+/// Bring the entry at `+N` or `-N` to the top, with the entries above it moved to the bottom
+fn rotate(position: &str, no_cd: bool) -> anyhow::Result<()> {
+    let mut stack = dir_stack::full_stack()?;
+
+    if stack.len() < 2 {
+        anyhow::bail!("no other directory");
+    }
+
+    let index = dir_stack::parse_position(position, stack.len())
+        .ok_or_else(|| anyhow::anyhow!("{position}: directory stack index out of range"))?;
+
+    if no_cd {
+        // Only the saved entries rotate, the working directory stays on top
+        let mut saved = stack.split_off(1);
+        let len = saved.len();
+        saved.rotate_left(index.saturating_sub(1) % len);
+        *write_dir_stack()? = saved;
+        return Ok(());
+    }
+
+    stack.rotate_left(index);
+    let top = stack.remove(0);
+
+    cd::change_dir(&top, false).map_err(|e| anyhow::anyhow!("{}: {e}", top.display()))?;
+    *write_dir_stack()? = stack;
+
+    Ok(())
+}
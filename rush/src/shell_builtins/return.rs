@@ -0,0 +1,95 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::{
+    executor::{Flow, request_flow},
+    vars::read_vars,
+};
+
+static BUILTIN_NAME: &str = "return";
+static DESC_STRING: &str = "Return from a shell function.\nreturn is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v] [n]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            (
+                "n",
+                "Status of the function, the last command's status by default",
+            ),
+        ];
+
+        let examples = [
+            format!("{} 1", BUILTIN_NAME),
+            "is_empty() { [ -z \"$1\" ] && return 0; return 1; }".to_string(),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut code = None;
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                _ if code.is_some() => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: too many arguments"),
+                    );
+                }
+                // Statuses wrap around like those of exited processes
+                value => match value.parse::<i64>() {
+                    Ok(value) => code = Some(value.rem_euclid(256) as u8),
+                    Err(_) => {
+                        return ExecResult::new(
+                            INVALID_ARGS,
+                            &format!("{BUILTIN_NAME}: {value}: numeric argument required"),
+                        );
+                    }
+                },
+            }
+        }
+
+        let code = code.unwrap_or_else(|| read_vars().map_or(0, |vars| vars.last_status()));
+
+        match request_flow(Flow::Return(code)) {
+            Ok(()) => ExecResult::new(code, ""),
+            Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        }
+    }
+}
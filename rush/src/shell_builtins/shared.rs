@@ -7,3 +7,8 @@ pub const INVALID_ARGS: u8 = 2;
 
 pub const NOT_A_PLUGIN: u8 = 64;
 pub const PLUGIN_NOT_FOUND: u8 = 65;
+
+/// Quote a value so the shell reads it back unchanged
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
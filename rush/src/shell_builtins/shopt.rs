@@ -0,0 +1,123 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::vars::{SHELL_OPTIONS, read_vars, write_vars};
+
+static BUILTIN_NAME: &str = "shopt";
+static DESC_STRING: &str = "Set and unset shell options.\nshopt is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -s | -u] [optname...]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-s", "Enables each optname"),
+            ("-u", "Disables each optname"),
+        ];
+
+        let examples = [
+            format!("{} -s nullglob", BUILTIN_NAME),
+            format!("{} failglob", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nAvailable options: {available}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            available = SHELL_OPTIONS.join(", "),
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut enable = None;
+        let mut names = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-s" => enable = Some(true),
+                "-u" => enable = Some(false),
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                name => names.push(name),
+            }
+        }
+
+        if let Some(enable) = enable {
+            let mut vars = match write_vars() {
+                Ok(vars) => vars,
+                Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            };
+
+            for name in names {
+                if let Err(e) = vars.set_option(name, enable) {
+                    return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}"));
+                }
+            }
+
+            return ExecResult::ok();
+        }
+
+        let vars = match read_vars() {
+            Ok(vars) => vars,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+
+        let names = if names.is_empty() {
+            SHELL_OPTIONS.to_vec()
+        } else {
+            names
+        };
+        let mut status = ExecResult::ok();
+
+        for name in names {
+            if !SHELL_OPTIONS.contains(&name) {
+                eprintln!("{BUILTIN_NAME}: {name}: invalid shell option name");
+                status = ExecResult::new(EXIT_FAILURE, "");
+                continue;
+            }
+
+            let state = if vars.option(name) { "on" } else { "off" };
+            println!("{name:<16}{state}");
+
+            // The status tells whether every listed option is on
+            if !vars.option(name) {
+                status = ExecResult::new(EXIT_FAILURE, "");
+            }
+        }
+
+        status
+    }
+}
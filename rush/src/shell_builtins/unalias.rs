@@ -0,0 +1,103 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::aliases::write_aliases;
+
+static BUILTIN_NAME: &str = "unalias";
+static DESC_STRING: &str = "Remove alias definitions.\nunalias is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!("Usage: {} [-h | -v | -a] [name...]", BUILTIN_NAME);
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("-v, --version", "Prints the version"),
+            ("-a", "Removes all aliases"),
+        ];
+
+        let examples = [
+            format!("{} ll", BUILTIN_NAME),
+            format!("{} -a", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut all = false;
+        let mut names = Vec::new();
+
+        for arg in args.iter() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "-v" | "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-a" => all = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                name => names.push(name),
+            }
+        }
+
+        if !all && names.is_empty() {
+            return ExecResult::new(
+                INVALID_ARGS,
+                &format!("{BUILTIN_NAME}: usage: {BUILTIN_NAME} [-a] name [name ...]"),
+            );
+        }
+
+        let mut aliases = match write_aliases() {
+            Ok(aliases) => aliases,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+
+        if all {
+            aliases.clear();
+            return ExecResult::ok();
+        }
+
+        let mut status = ExecResult::ok();
+
+        for name in names {
+            if !aliases.remove(name) {
+                eprintln!("{BUILTIN_NAME}: {name}: not found");
+                status = ExecResult::new(EXIT_FAILURE, "");
+            }
+        }
+
+        status
+    }
+}
@@ -0,0 +1,127 @@
+use abi_stable::std_types::{RString, RVec};
+use rush_interface::ExecResult;
+
+use super::{
+    BuiltinCommand,
+    shared::{EXIT_FAILURE, INVALID_ARGS},
+};
+use crate::{
+    functions::write_functions,
+    vars::{self, write_vars},
+};
+
+static BUILTIN_NAME: &str = "unset";
+static DESC_STRING: &str =
+    "Unset values of shell variables and functions.\nunset is a shell built-in";
+
+pub(super) struct Command;
+
+impl BuiltinCommand for Command {
+    fn print_help(&self) {
+        let usage = format!(
+            "Usage: {} [-h | --version] [-f | -v] [--] [name...]",
+            BUILTIN_NAME
+        );
+        let options = [
+            ("-h, --help", "Prints this help message"),
+            ("--version", "Prints the version"),
+            ("-f", "Treats each name as a shell function"),
+            ("-v", "Treats each name as a shell variable, the default"),
+            ("--", "Ends the options, the arguments after it are names"),
+        ];
+
+        let examples = [
+            format!("{} EDITOR", BUILTIN_NAME),
+            format!("{} FOO BAR", BUILTIN_NAME),
+            format!("{} -f greet", BUILTIN_NAME),
+        ];
+
+        let options_text = options
+            .iter()
+            .map(|(opt, desc)| format!("  {}: {}", opt, desc))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let examples_text = examples.join("\n");
+
+        eprintln!(
+            "{desc}\n\n{usage}\n\nOptions:\n{options}\n\nExamples:\n{examples}",
+            desc = DESC_STRING,
+            usage = usage,
+            options = options_text,
+            examples = examples_text,
+        )
+    }
+
+    fn print_version(&self) {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+    }
+
+    fn execute(&self, args: RVec<RString>) -> ExecResult {
+        let mut functions = false;
+        let mut names = Vec::new();
+        let mut options_done = false;
+
+        for arg in args.iter() {
+            // Options stop at the first name, so that later names may start with `-`
+            if options_done {
+                names.push(arg.as_str());
+                continue;
+            }
+
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    self.print_help();
+                    return ExecResult::ok();
+                }
+                "--version" => {
+                    self.print_version();
+                    return ExecResult::ok();
+                }
+                "-f" => functions = true,
+                "-v" => functions = false,
+                "--" => options_done = true,
+                opt if opt.starts_with('-') && opt.len() > 1 => {
+                    return ExecResult::new(
+                        INVALID_ARGS,
+                        &format!("{BUILTIN_NAME}: {opt}: invalid option"),
+                    );
+                }
+                name => {
+                    options_done = true;
+                    names.push(name);
+                }
+            }
+        }
+
+        if functions {
+            return match write_functions() {
+                Ok(mut table) => {
+                    for name in names {
+                        table.remove(name);
+                    }
+                    ExecResult::ok()
+                }
+                Err(e) => ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+            };
+        }
+
+        let mut vars = match write_vars() {
+            Ok(vars) => vars,
+            Err(e) => return ExecResult::new(EXIT_FAILURE, &format!("{BUILTIN_NAME}: {e}")),
+        };
+        let mut status = ExecResult::ok();
+
+        for name in names {
+            if !vars::is_valid_name(name) {
+                eprintln!("{BUILTIN_NAME}: `{name}': not a valid identifier");
+                status = ExecResult::new(EXIT_FAILURE, "");
+                continue;
+            }
+
+            vars.unset(name);
+        }
+
+        status
+    }
+}
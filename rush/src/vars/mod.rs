@@ -0,0 +1,345 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use nix::unistd::{Pid, getpid};
+
+static VARS: OnceLock<RwLock<VarStore>> = OnceLock::new();
+
+/// Options that can be turned on and off with `shopt`
+pub const SHELL_OPTIONS: [&str; 2] = ["failglob", "nullglob"];
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub value: String,
+    /// Exported variables are mirrored into the process environment
+    pub exported: bool,
+}
+
+/// Shell variables and the special parameters derived from the shell's state
+pub struct VarStore {
+    vars: HashMap<String, Variable>,
+    /// `$0`, the script being run or the shell itself
+    shell_name: String,
+    /// `$1`, `$2`, ...
+    positional: Vec<String>,
+    /// Values hidden by `local` in each running function, restored when it returns
+    scopes: Vec<HashMap<String, Option<Variable>>>,
+    /// `$?`
+    last_status: u8,
+    /// Status of the latest command substitution, for commands without a name
+    substitution_status: Option<u8>,
+    /// `$$`, kept from startup so subshells report the same value
+    shell_pid: Pid,
+    /// Names from `SHELL_OPTIONS` that are turned on
+    options: HashSet<&'static str>,
+}
+
+impl Default for VarStore {
+    fn default() -> Self {
+        Self {
+            vars: HashMap::new(),
+            shell_name: "rush".to_string(),
+            positional: Vec::new(),
+            scopes: Vec::new(),
+            last_status: 0,
+            substitution_status: None,
+            shell_pid: getpid(),
+            options: HashSet::new(),
+        }
+    }
+}
+
+impl VarStore {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|var| var.value.as_str())
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.vars.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.vars.keys().map(String::as_str)
+    }
+
+    /// Set a variable, keeping the environment in sync if it is exported
+    pub fn set(&mut self, name: &str, value: &str) {
+        let var = self
+            .vars
+            .entry(name.to_string())
+            .or_insert_with(|| Variable {
+                value: String::new(),
+                exported: false,
+            });
+
+        var.value = value.to_string();
+
+        if var.exported {
+            set_env(name, value);
+        }
+    }
+
+    /// Put a variable back into a previous state, unsetting it for `None`
+    pub fn restore(&mut self, name: &str, var: Option<Variable>) {
+        match var {
+            Some(var) => {
+                if var.exported {
+                    set_env(name, &var.value);
+                } else {
+                    remove_env(name);
+                }
+                self.vars.insert(name.to_string(), var);
+            }
+            None => self.unset(name),
+        }
+    }
+
+    /// Mark a variable for export, creating it empty if needed
+    pub fn export(&mut self, name: &str) {
+        let var = self
+            .vars
+            .entry(name.to_string())
+            .or_insert_with(|| Variable {
+                value: String::new(),
+                exported: false,
+            });
+
+        var.exported = true;
+        set_env(name, &var.value);
+    }
+
+    /// Keep a variable in the shell but remove it from the environment
+    pub fn unexport(&mut self, name: &str) {
+        if let Some(var) = self.vars.get_mut(name) {
+            var.exported = false;
+            remove_env(name);
+        }
+    }
+
+    pub fn unset(&mut self, name: &str) {
+        if self.vars.remove(name).is_some_and(|var| var.exported) {
+            remove_env(name);
+        }
+    }
+
+    /// Exported variables sorted by name
+    pub fn exported(&self) -> Vec<(&str, &str)> {
+        let mut exported: Vec<(&str, &str)> = self
+            .vars
+            .iter()
+            .filter(|(_, var)| var.exported)
+            .map(|(name, var)| (name.as_str(), var.value.as_str()))
+            .collect();
+
+        exported.sort();
+        exported
+    }
+
+    pub fn shell_name(&self) -> &str {
+        &self.shell_name
+    }
+
+    pub fn set_shell_name(&mut self, name: &str) {
+        self.shell_name = name.to_string();
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Replace `$1`, `$2`, ..., returning the previous ones
+    pub fn set_positional(&mut self, args: Vec<String>) -> Vec<String> {
+        std::mem::replace(&mut self.positional, args)
+    }
+
+    /// Start a scope for the variables a function declares `local`
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Leave a function's scope, bringing back the variables its locals hid
+    pub fn pop_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            for (name, var) in scope {
+                self.restore(&name, var);
+            }
+        }
+    }
+
+    /// Make a variable local to the running function, it starts unset
+    pub fn make_local(&mut self, name: &str) -> anyhow::Result<()> {
+        let Some(scope) = self.scopes.last_mut() else {
+            anyhow::bail!("can only be used in a function");
+        };
+
+        if !scope.contains_key(name) {
+            scope.insert(name.to_string(), self.vars.get(name).cloned());
+            self.unset(name);
+        }
+
+        Ok(())
+    }
+
+    pub fn last_status(&self) -> u8 {
+        self.last_status
+    }
+
+    pub fn shell_pid(&self) -> Pid {
+        self.shell_pid
+    }
+
+    pub fn option(&self, name: &str) -> bool {
+        self.options.contains(name)
+    }
+
+    pub fn set_option(&mut self, name: &str, enabled: bool) -> anyhow::Result<()> {
+        let Some(&name) = SHELL_OPTIONS.iter().find(|&&option| option == name) else {
+            anyhow::bail!("{name}: invalid shell option name");
+        };
+
+        if enabled {
+            self.options.insert(name);
+        } else {
+            self.options.remove(name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `name` can be assigned to, as opposed to special and positional parameters
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn set_env(name: &str, value: &str) {
+    // SAFETY: the shell runs commands and plugins on a single thread
+    unsafe { env::set_var(name, value) };
+}
+
+fn remove_env(name: &str) {
+    // SAFETY: the shell runs commands and plugins on a single thread
+    unsafe { env::remove_var(name) };
+}
+
+/// Assignments written before a command, undone when the command is done
+#[derive(Default)]
+pub struct TemporaryVars {
+    saved: Vec<(String, Option<Variable>)>,
+}
+
+impl Drop for TemporaryVars {
+    fn drop(&mut self) {
+        if let Ok(mut vars) = write_vars() {
+            for (name, var) in self.saved.drain(..).rev() {
+                vars.restore(&name, var);
+            }
+        }
+    }
+}
+
+/// Export variables for the duration of a single command
+pub fn set_temporary(assignments: &[(String, String)]) -> anyhow::Result<TemporaryVars> {
+    let mut vars = write_vars()?;
+    let mut temporary = TemporaryVars::default();
+
+    for (name, value) in assignments {
+        temporary
+            .saved
+            .push((name.clone(), vars.variable(name).cloned()));
+        vars.set(name, value);
+        vars.export(name);
+    }
+
+    Ok(temporary)
+}
+
+fn is_same_dir(pwd: &str, dir: &Path) -> bool {
+    let pwd = Path::new(pwd);
+
+    match (fs::metadata(pwd), fs::metadata(dir)) {
+        (Ok(a), Ok(b)) => pwd.is_absolute() && a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+fn var_store() -> &'static RwLock<VarStore> {
+    VARS.get_or_init(|| RwLock::new(VarStore::default()))
+}
+
+pub fn read_vars() -> anyhow::Result<RwLockReadGuard<'static, VarStore>> {
+    var_store()
+        .read()
+        .map_err(|_| anyhow::anyhow!("VARS read lock poisoned"))
+}
+
+pub fn write_vars() -> anyhow::Result<RwLockWriteGuard<'static, VarStore>> {
+    var_store()
+        .write()
+        .map_err(|_| anyhow::anyhow!("VARS write lock poisoned"))
+}
+
+/// Set a shell variable
+pub fn assign(name: &str, value: &str) -> anyhow::Result<()> {
+    write_vars()?.set(name, value);
+    Ok(())
+}
+
+/// Remember the status of the last command for `$?`
+pub fn set_last_status(code: u8) {
+    if let Ok(mut vars) = write_vars() {
+        vars.last_status = code;
+    }
+}
+
+/// Remember the status of a command substitution, which also becomes `$?`
+pub fn set_substitution_status(code: u8) {
+    if let Ok(mut vars) = write_vars() {
+        vars.last_status = code;
+        vars.substitution_status = Some(code);
+    }
+}
+
+/// Take the status of the latest command substitution since the last call
+pub fn take_substitution_status() -> Option<u8> {
+    write_vars()
+        .ok()
+        .and_then(|mut vars| vars.substitution_status.take())
+}
+
+pub fn init_module() -> anyhow::Result<()> {
+    let mut vars = write_vars()?;
+
+    // The environment the shell was started with becomes its first variables
+    for (name, value) in env::vars() {
+        vars.vars.insert(
+            name,
+            Variable {
+                value,
+                exported: true,
+            },
+        );
+    }
+
+    // An inherited PWD is only kept while it still names the current directory
+    let current_dir = env::current_dir()?;
+    if !vars
+        .get("PWD")
+        .is_some_and(|pwd| is_same_dir(pwd, &current_dir))
+    {
+        vars.set("PWD", &current_dir.to_string_lossy());
+        vars.export("PWD");
+    }
+
+    Ok(())
+}